@@ -18,6 +18,135 @@ void ResetFStringBuffer(const FString& fstr, RefString& utfstr) {
 void ResetFTextBuffer(const FText& fstr, RefString& utfstr) {
     ResetFStringBuffer(fstr.ToString(), utfstr);
 }
+//copy rust slice into TArray
+template<typename T>
+TArray<T> ToTArray(const void* data, uint32 len) {
+    if (data && len > 0)
+        return TArray<T>((const T*)data, len);
+    else
+        return TArray<T>();
+}
+//copy rust slice of R into TArray of T
+template<typename T, typename R, typename F>
+TArray<T> ToTArray(const void* data, uint32 len, F convert) {
+    TArray<T> result;
+    if (data && len > 0) {
+        result.Reserve(len);
+        for (uint32 i = 0; i < len; i++) {
+            result.Add(convert(((const R*)data)[i]));
+        }
+    }
+    return result;
+}
+//copy TArray into rust vec
+template<typename T>
+void ResetRustArray(const TArray<T>& arr, RefArray& out) {
+    T* dst = (T*)out.reserve(out.vec_ref, arr.Num());
+    if (dst && arr.Num() > 0) {
+        memcpy(dst, arr.GetData(), sizeof(T) * arr.Num());
+        out.set_len(out.vec_ref, arr.Num());
+    }
+}
+//copy TArray of T into rust vec of R
+template<typename T, typename R, typename F>
+void ResetRustArray(const TArray<T>& arr, RefArray& out, F convert) {
+    R* dst = (R*)out.reserve(out.vec_ref, arr.Num());
+    if (dst) {
+        for (int32 i = 0; i < arr.Num(); i++) {
+            new (&dst[i]) R(convert(arr[i]));
+        }
+        out.set_len(out.vec_ref, arr.Num());
+    }
+}
+//copy TArray of objects into rust vec, null objects are skipped
+template<typename T>
+void ResetRustObjectArray(const TArray<T*>& arr, RefArray& out) {
+    uint32 len = 0;
+    for (auto obj : arr) {
+        if (obj) len++;
+    }
+    void** dst = (void**)out.reserve(out.vec_ref, len);
+    if (dst) {
+        uint32 written = 0;
+        for (auto obj : arr) {
+            if (obj) dst[written++] = (void*)obj;
+        }
+        out.set_len(out.vec_ref, written);
+    }
+}
+template<typename T>
+bool IsNullElement(const T&) {
+    return false;
+}
+template<typename T>
+bool IsNullElement(T* const& obj) {
+    return obj == nullptr;
+}
+//build TMap from keys and values
+template<typename K, typename V>
+TMap<K, V> ToTMap(const TArray<K>& keys, const TArray<V>& values) {
+    TMap<K, V> result;
+    result.Reserve(keys.Num());
+    for (int32 i = 0; i < keys.Num() && i < values.Num(); i++) {
+        result.Add(keys[i], values[i]);
+    }
+    return result;
+}
+//split TMap into keys and values, pairs with null object are skipped
+template<typename K, typename V>
+void SplitTMap(const TMap<K, V>& map, TArray<K>& keys, TArray<V>& values) {
+    keys.Reserve(map.Num());
+    values.Reserve(map.Num());
+    for (auto& pair : map) {
+        if (IsNullElement(pair.Key) || IsNullElement(pair.Value)) continue;
+        keys.Add(pair.Key);
+        values.Add(pair.Value);
+    }
+}
+//checked cast of unreal objects, false if uclass is null
+bool uapi_IsA(void* target, void* uclass) {
+    return target && uclass && ((UObject*)target)->IsA((UClass*)uclass);
+}
+static_assert(sizeof(WeakObjectPtr) == sizeof(FWeakObjectPtr), "WeakObjectPtr should be same as FWeakObjectPtr");
+template<typename T>
+WeakObjectPtr ToWeakObjectPtr(const TWeakObjectPtr<T>& ptr) {
+    WeakObjectPtr result;
+    FMemory::Memcpy(&result, &ptr, sizeof(WeakObjectPtr));
+    return result;
+}
+template<typename T>
+TWeakObjectPtr<T> ToTWeakObjectPtr(const WeakObjectPtr& ptr) {
+    TWeakObjectPtr<T> result;
+    FMemory::Memcpy(&result, &ptr, sizeof(WeakObjectPtr));
+    return result;
+}
+//object of weak ptr, null if it is destroyed
+void* uapi_GetWeakObject(WeakObjectPtr ptr) {
+    FWeakObjectPtr weak;
+    FMemory::Memcpy(&weak, &ptr, sizeof(WeakObjectPtr));
+    return weak.Get();
+}
+WeakObjectPtr uapi_MakeWeakObject(void* target) {
+    FWeakObjectPtr weak((UObject*)target);
+    WeakObjectPtr result;
+    FMemory::Memcpy(&result, &weak, sizeof(WeakObjectPtr));
+    return result;
+}
+//rooted trampoline of rust closure, released when it is removed from delegate
+URustDelegate* NewRustDelegate(void* closure, void* callback, forward_rust_delegate_handler forward, drop_rust_closure_handler drop) {
+    auto trampoline = NewObject<URustDelegate>();
+    trampoline->closure = closure;
+    trampoline->callback = callback;
+    trampoline->forward = forward;
+    trampoline->drop = drop;
+    trampoline->AddToRoot();
+    return trampoline;
+}
+void ReleaseRustDelegate(void* handle) {
+    if (auto trampoline = (URustDelegate*)handle) {
+        trampoline->Release();
+    }
+}
 using create_native_string_handler = char* (*)(const char* c_str, uint32);
 create_native_string_handler create_native_string = nullptr;
 
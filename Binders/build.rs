@@ -1,14 +1,14 @@
 
 // #[macro_use] 
 // extern crate serde_json;
-use std::{env, path::PathBuf};
+use std::env;
 
 
-pub const CORE_HEADER: &'static str = "def.h";
+pub const CORE_HEADER: &str = "def.h";
 #[allow(unreachable_code)]
 fn main(){
     return;
-    let workspace = PathBuf::from(env::current_dir().unwrap()).join("BaseTypes/").as_path().display().to_string().replace("\\", "/");
+    let workspace = env::current_dir().unwrap().join("BaseTypes/").as_path().display().to_string().replace("\\", "/");
     let export_header = format!("{}{}", workspace, CORE_HEADER);
     println!("cargo:rerun-if-changed={}",export_header);
     let bindings = bindgen::Builder::default()
@@ -0,0 +1,39 @@
+#pragma once
+#include "CoreMinimal.h"
+#include "UObject/Object.h"
+#include "RustDelegate.generated.h"
+
+//forward params of delegate broadcast to rust callback
+using forward_rust_delegate_handler = void(*)(void* callback, void* closure, void* parms);
+using drop_rust_closure_handler = void(*)(void* closure);
+//trampoline of rust closure bound to dynamic delegate, every broadcast is processed as Invoke
+UCLASS()
+class URustDelegate : public UObject {
+    GENERATED_BODY()
+public:
+    void* closure = nullptr;
+    void* callback = nullptr;
+    forward_rust_delegate_handler forward = nullptr;
+    drop_rust_closure_handler drop = nullptr;
+    UFUNCTION()
+    void Invoke() {}
+    virtual void ProcessEvent(UFunction* Function, void* Parms) override {
+        if (forward && closure) {
+            forward(callback, closure, Parms);
+        }
+    }
+    //drop rust closure and let gc collect the trampoline
+    void Release() {
+        if (drop && closure) {
+            drop(closure);
+        }
+        closure = nullptr;
+        if (IsRooted()) {
+            RemoveFromRoot();
+        }
+    }
+    virtual void BeginDestroy() override {
+        Release();
+        Super::BeginDestroy();
+    }
+};
@@ -1,5 +1,10 @@
 #pragma once
 #include "RustApi.h"
+#include "RustDelegate.h"
+
+//math wrappers of wrapper.rs and their ToVector3/ToFVector.. are defined by RustApi.h with `Real`
+static_assert(sizeof(Vector2) == sizeof(Real) * 2 && sizeof(Vector3) == sizeof(Real) * 3 && sizeof(Rotator) == sizeof(Real) * 3 &&
+    sizeof(Quaternion) == sizeof(Real) * 4 && sizeof(Transform) == sizeof(Real) * 10, "math wrappers of RustApi.h should be packed Real");
 
 //thread unsafe
 struct NativeString {
@@ -12,6 +17,26 @@ struct RefString {
     void* str_ref;
     uint32 size;
 };
+//TArray view of rust slice
+struct NativeArray {
+    const void* data;
+    uint32 len;
+};
+//rust vec which can be refilled by native side
+struct RefArray {
+    const void* data;
+    uint32 len;
+    void* vec_ref;
+    //clear rust vec, reserve len elements and return the uninitialized buffer
+    void* (*reserve)(void* vec_ref, uint32 len);
+    //set len of rust vec after the first len elements of the buffer are written
+    void (*set_len)(void* vec_ref, uint32 len);
+};
+//TWeakObjectPtr across ffi, same layout as FWeakObjectPtr
+struct WeakObjectPtr {
+    int32 index;
+    int32 serial;
+};
 FString Utf82FString(const NativeString& utfstr);
 FString Utf8Ref2FString(const RefString& utfstr);
 FName Utf82FName(const NativeString& utfstr);
@@ -1,27 +1,30 @@
 
-use glam::{Quat, Vec3};
+///precision of math wrappers(Vector2, Vector3, Vector4, Rotator, Quaternion, Transform), f64 if LargeWorldCoordinates is set
+pub type Real = f32;
+pub type RealVec3 = glam::Vec3;
+pub type RealQuat = glam::Quat;
 #[repr(C)]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Vector3 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: Real,
+    pub y: Real,
+    pub z: Real,
 }
 #[repr(C)]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Quaternion {
-    x: f32,
-    y: f32,
-    z: f32,
-    w: f32,
+    x: Real,
+    y: Real,
+    z: Real,
+    w: Real,
 }
 #[repr(C)]
-#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Entity {
     pub id: u64,
 }
 #[repr(C)]
-#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -50,20 +53,11 @@ impl Color {
         a: 255,
     };
 }
-#[repr(C)]
-#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Uuid {
-    pub a: u32,
-    pub b: u32,
-    pub c: u32,
-    pub d: u32,
-}
-
 #[repr(C)]
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Vector2 {
-    pub x: f32,
-    pub y: f32,
+    pub x: Real,
+    pub y: Real,
 }
 
 pub type Vector4 = Quaternion;
@@ -128,17 +122,17 @@ pub struct SoundSettings {
 #[derive(Default, Clone, Copy, Debug, PartialEq)]
 pub struct Rotator {
     /** Rotation around the right axis (around Y axis), Looking up and down (0=Straight Ahead, +Up, -Down) */
-    pub pitch: f32,
+    pub pitch: Real,
 
     /** Rotation around the up axis (around Z axis), Turning around (0=Forward, +Right, -Left)*/
-    pub yaw: f32,
+    pub yaw: Real,
 
     /** Rotation around the forward axis (around X axis), Tilting your head, (0=Straight, +Clockwise, -CCW) */
-    pub roll: f32,
+    pub roll: Real,
 }
 
 #[repr(C)]
-#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct IntPoint
 {
     pub x: i32,
@@ -153,20 +147,20 @@ pub struct Transform
     pub scale: Vector3
 }
 
-impl From<Quaternion> for Quat {
+impl From<Quaternion> for RealQuat {
     fn from(val: Quaternion) -> Self {
-        Quat::from_xyzw(val.x, val.y, val.z, val.w)
+        RealQuat::from_xyzw(val.x, val.y, val.z, val.w)
     }
 }
 
-impl From<Vector3> for Vec3 {
+impl From<Vector3> for RealVec3 {
     fn from(val: Vector3) -> Self {
-        Vec3::new(val.x, val.y, val.z)
+        RealVec3::new(val.x, val.y, val.z)
     }
 }
 
-impl From<Vec3> for Vector3 {
-    fn from(v: Vec3) -> Self {
+impl From<RealVec3> for Vector3 {
+    fn from(v: RealVec3) -> Self {
         Vector3 {
             x: v.x,
             y: v.y,
@@ -174,8 +168,8 @@ impl From<Vec3> for Vector3 {
         }
     }
 }
-impl From<Quat> for Quaternion {
-    fn from(v: Quat) -> Self {
+impl From<RealQuat> for Quaternion {
+    fn from(v: RealQuat) -> Self {
         Quaternion {
             x: v.x,
             y: v.y,
@@ -188,7 +182,7 @@ impl From<Quat> for Quaternion {
 unreal FName
  */
 #[repr(C)]
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct UName{
 	pub entry: u32,
 	pub number: u32,
@@ -199,9 +193,105 @@ pub trait IPtr: Sized{
     fn from_ptr(ptr: *mut c_void) -> Option<Self>;
 }
 ///cast V into R, this casting is unsafe, user should ensure the safety
+///(use try_cast of IUObject for checked cast)
 pub unsafe fn cast_to<V: IPtr, R: IPtr>(from: V) -> Option<R>{
     R::from_ptr(from.inner())
 }
+///imply that this is a unreal UObject with UClass
+pub trait IUObject: IPtr{
+    ///UClass of this type, null if not registered
+    fn static_class() -> *mut c_void;
+    ///checked cast by UObject::IsA, None if this object is not a R
+    fn try_cast<R: IUObject>(&self) -> Option<R>{
+        let is_a = try_bindings().and_then(|bindings| bindings.uapi_IsA)
+        .map(|is_a| unsafe{ is_a(self.inner(), R::static_class()) })
+        .unwrap_or_default();
+        if_else!(is_a, R::from_ptr(self.inner()), None)
+    }
+}
+///TSubclassOf<T>, UClass of T or its subclass
+#[repr(transparent)]
+pub struct SubclassOf<T>{
+    class: *mut c_void,
+    _marker: std::marker::PhantomData<T>,
+}
+impl<T> Clone for SubclassOf<T>{
+    fn clone(&self) -> Self{ *self }
+}
+impl<T> Copy for SubclassOf<T>{}
+impl<T> SubclassOf<T>{
+    ///class should be T or its subclass
+    pub unsafe fn from_class(class: *mut c_void) -> Self{
+        Self{ class, _marker: std::marker::PhantomData }
+    }
+    pub fn class(&self) -> *mut c_void{
+        self.class
+    }
+    pub fn is_null(&self) -> bool{
+        self.class.is_null()
+    }
+}
+impl<T: IUObject> SubclassOf<T>{
+    ///UClass of T
+    pub fn of() -> Self{
+        unsafe{ Self::from_class(T::static_class()) }
+    }
+}
+///TWeakObjectPtr<T>, object is checked alive on access
+#[repr(C)]
+pub struct WeakObjectPtr<T>{
+    index: i32,
+    serial: i32,
+    _marker: std::marker::PhantomData<T>,
+}
+impl<T> Clone for WeakObjectPtr<T>{
+    fn clone(&self) -> Self{ *self }
+}
+impl<T> Copy for WeakObjectPtr<T>{}
+impl<T> Default for WeakObjectPtr<T>{
+    fn default() -> Self{
+        Self{ index: 0, serial: 0, _marker: std::marker::PhantomData }
+    }
+}
+impl<T> WeakObjectPtr<T>{
+    fn cast<R>(self) -> WeakObjectPtr<R>{
+        WeakObjectPtr{ index: self.index, serial: self.serial, _marker: std::marker::PhantomData }
+    }
+}
+impl<T: IPtr> WeakObjectPtr<T>{
+    pub fn new(target: &T) -> Self{
+        try_bindings().and_then(|bindings| bindings.uapi_MakeWeakObject)
+        .map(|make| unsafe{ make(target.inner()) }.cast())
+        .unwrap_or_default()
+    }
+    ///object if it is still alive
+    pub fn get(&self) -> Option<T>{
+        try_bindings().and_then(|bindings| bindings.uapi_GetWeakObject)
+        .and_then(|get| T::from_ptr(unsafe{ get(self.cast()) }))
+    }
+    pub fn is_valid(&self) -> bool{
+        self.get().is_some()
+    }
+}
+///native function of Binder.cpp is not registered(see verify_all_handlers)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindingError(pub &'static str);
+impl std::fmt::Display for BindingError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        write!(f, "unreal binding {} is not registered", self.0)
+    }
+}
+impl std::error::Error for BindingError{}
+///rust closure bound to dynamic multicast delegate, returned by `add_*` and consumed by `remove_*`
+#[repr(transparent)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct DelegateHandle{
+    inner: *mut c_void,
+}
+///drop boxed closure when trampoline is released
+unsafe extern "C" fn drop_closure<T>(closure: *mut c_void){
+    drop(Box::from_raw(closure as *mut T));
+}
 pub fn char_str_2_string(cstr: *const std::os::raw::c_char) -> String{
     if cstr.is_null(){
         error!("translate string by null ptr");
@@ -241,6 +331,59 @@ pub struct RefString{
     pub str_ref: *mut String,
     pub size: u32,
 }
+///rust slice as TArray parameter
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NativeArray{
+    pub data: *const c_void,
+    pub len: u32,
+}
+impl NativeArray{
+    pub fn new<T>(slice: &[T]) -> Self{
+        Self{ data: slice.as_ptr() as *const c_void, len: slice.len() as u32 }
+    }
+}
+///rust vec as TArray parameter or result, refilled by native side
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RefArray{
+    pub data: *const c_void,
+    pub len: u32,
+    pub vec_ref: *mut c_void,
+    pub reserve: unsafe extern "C" fn(vec_ref: *mut c_void, len: u32) -> *mut c_void,
+    pub set_len: unsafe extern "C" fn(vec_ref: *mut c_void, len: u32),
+}
+impl RefArray{
+    pub fn new<T>(vec: &mut Vec<T>) -> Self{
+        Self{
+            data: vec.as_ptr() as *const c_void,
+            len: vec.len() as u32,
+            vec_ref: vec as *mut Vec<T> as *mut c_void,
+            reserve: reserve_ref_array::<T>,
+            set_len: set_ref_array_len::<T>,
+        }
+    }
+}
+///native side writes into the spare capacity, elements are not visible until set_len
+unsafe extern "C" fn reserve_ref_array<T>(vec_ref: *mut c_void, len: u32) -> *mut c_void{
+    let Some(vec) = (vec_ref as *mut Vec<T>).as_mut()
+    else{
+        return std::ptr::null_mut();
+    };
+    vec.clear();
+    vec.reserve_exact(len as usize);
+    vec.spare_capacity_mut().as_mut_ptr() as *mut c_void
+}
+///native side reports how many elements it wrote after reserve
+unsafe extern "C" fn set_ref_array_len<T>(vec_ref: *mut c_void, len: u32){
+    let Some(vec) = (vec_ref as *mut Vec<T>).as_mut()
+    else{
+        return;
+    };
+    if vec.is_empty() && len as usize <= vec.capacity(){
+        vec.set_len(len as usize);
+    }
+}
 /// rust string to c const string(as parameter)
 /// ```
 /// pub fn string_2_char_str(rstr: &str) -> binders::NativeString{
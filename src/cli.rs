@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::engine_parser::CustomSettings;
+///unreal engine c++ to rust binder generator
+#[derive(Debug, Parser)]
+#[command(name = "cpp_builder", version)]
+pub struct Cli{
+    ///custom settings json
+    #[arg(short, long, global = true, default_value = "configs/CustomSettings.json")]
+    pub config: PathBuf,
+    ///override EngineRoot of the settings
+    #[arg(long, global = true)]
+    pub engine_root: Option<String>,
+    ///binder output directory, cpp/ and rs/ will be created inside
+    #[arg(short, long, global = true)]
+    pub output: Option<String>,
+    ///directory of engine_code/, unreal_ast/ and engine.json
+    #[arg(long, global = true)]
+    pub cache_dir: Option<String>,
+    ///clang worker threads
+    #[arg(short = 'j', long, global = true)]
+    pub threads: Option<usize>,
+    ///all if not set
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum Command{
+    ///strip engine headers into engine_code
+    Scan,
+    ///dump engine_code with clang and save the engine model
+    Parse,
+    ///generate binders from the saved engine model, parse again if missing or outdated
+    Generate,
+    ///scan, parse and generate, the saved engine model is reused if no header was changed
+    All,
+    ///remove engine_code, unreal_ast and the saved engine model
+    CleanCache,
+}
+impl Cli{
+    ///load settings from config file with command line overrides
+    pub fn load_settings(&self) -> anyhow::Result<CustomSettings>{
+        let file = std::fs::File::open(&self.config)
+        .map_err(|e| anyhow::anyhow!("fail to open config {}: {e}", self.config.display()))?;
+        let mut settings: CustomSettings = serde_json::from_reader(file)?;
+        if let Some(engine_root) = &self.engine_root{
+            settings.EngineRoot = engine_root.clone();
+        }
+        if let Some(output) = &self.output{
+            settings.OutputDir = output.clone();
+        }
+        if let Some(cache_dir) = &self.cache_dir{
+            settings.CacheDir = cache_dir.clone();
+        }
+        if let Some(threads) = self.threads{
+            settings.ThreadCount = threads;
+        }
+        Ok(settings)
+    }
+}
@@ -1,8 +1,8 @@
 #![allow(non_snake_case)]
-use std::{path::Path, sync::{Mutex, Arc}, fmt::{Debug}};
+use std::{collections::BTreeMap, path::{Path, PathBuf}, sync::{Mutex, Arc}, fmt::{Debug}};
 use serde::Deserialize;
 
-use super::{unreal_engine::{Engine, UnrealClass}, config::{CppEnum, CppApi, Parameter, CppProperty, CppEnumConstant}};
+use super::{manifest::{self, AstManifest, ManifestEntry}, unreal_engine::{Engine, UnrealClass}, config::{CppEnum, CppApi, Parameter, CppProperty, CppEnumConstant, CustomSettings, Specifiers, Container, CppDelegate}};
 pub type Node = clang_ast::Node<Clang>;
 
 #[derive(Deserialize)]
@@ -26,6 +26,9 @@ pub struct Clang {
     pub scopedEnumTag: Option<String>,
     #[serde(default)]
     pub explicitlyDeleted: bool,
+    ///base classes of CXXRecordDecl
+    #[serde(default)]
+    pub bases: Vec<BaseSpecifier>,
 
 }
 impl Debug for Clang{
@@ -42,10 +45,21 @@ impl Debug for Clang{
     }
 }
 #[derive(Deserialize)]
+pub struct BaseSpecifier {
+    #[serde(default)]
+    access: String,
+    #[serde(default)]
+    isVirtual: bool,
+    r#type: Option<QualType>,
+}
+#[derive(Deserialize)]
 pub struct QualType {
     qualType: Option<String>
 }
-pub fn run(engine: &mut Engine) -> anyhow::Result<()>{
+///dump and parse headers changed since last run,
+///entries of unchanged headers are kept if `engine` holds the saved model
+///, returns the manifest to save along with the model
+pub fn run(engine: &mut Engine, settings: &CustomSettings, incremental: bool) -> anyhow::Result<AstManifest>{
     //https://www.cnblogs.com/kuliuheng/p/10769192.html
     //clang -Xclang -ast-dump -fsyntax-only -Iinclude -x c++ test.h > out.txt
 
@@ -55,64 +69,133 @@ pub fn run(engine: &mut Engine) -> anyhow::Result<()>{
 
     //clang -Xclang -ast-dump=json -fsyntax-only -x c++ engine_code/Actor.h > out.txt
     let current_dir = std::env::current_dir()?;
-    let files = crate::read_files("engine_code\\", "*.h")?;
-    let target_dir = current_dir.join("unreal_ast").display().to_string().replace("/", "\\");
-    std::fs::create_dir(&target_dir).ok();
-    let thread_count = 
-    // 1;
-    num_cpus::get();
-    let step = files.len() / thread_count;
+    let code_dir = current_dir.join(settings.engine_code_dir());
+    let target_dir = current_dir.join(settings.ast_dir());
+    std::fs::create_dir_all(&target_dir).ok();
+    let clang = settings.clang_path().to_string();
+    let clang_args = clang_args(settings);
+    let layout_args = settings.DumpRecordLayouts.then(|| layout_args(&clang_args));
+    let clang_version = clang_version(&clang)?;
+    engine.pointer_size = pointer_size(&clang, settings);
+    let old_manifest = AstManifest::load(settings);
+    let mut manifest = AstManifest::default();
+    //(relative path, need dump)
+    let mut files = vec![];
+    //current hashes of included headers
+    let mut dependency_hashes = BTreeMap::new();
+    for (relative_path, source_hash) in &engine.source_hashes {
+        let Ok(content) = std::fs::read(code_dir.join(relative_path)) else{
+            continue;
+        };
+        let old_entry = old_manifest.files.get(relative_path);
+        let mut entry = ManifestEntry{
+            source_hash: source_hash.clone(),
+            preprocessed_hash: manifest::hash_content(&content),
+            clang_version: clang_version.clone(),
+            flags: clang_args.iter().chain(layout_args.iter().flatten()).cloned().collect(),
+            dependencies: old_entry.map(|entry| entry.dependencies.clone()).unwrap_or_default(),
+        };
+        let unchanged = old_entry == Some(&entry) && !entry.dependencies_changed(&mut dependency_hashes) &&
+            dump_path(&target_dir, relative_path).exists() &&
+            (layout_args.is_none() || layout_path(&target_dir, relative_path).exists());
+        if !unchanged{
+            //filled from the depfile of the new dump
+            entry.dependencies.clear();
+        }
+        if !unchanged || !incremental{
+            files.push((relative_path.clone(), !unchanged));
+        }
+        manifest.files.insert(relative_path.clone(), entry);
+    }
+    //entries of removed and changed headers
+    engine.classes.retain(|c| manifest.files.contains_key(&c.path) && !files.iter().any(|(path, _)| path == &c.path));
+    engine.enums.retain(|e| manifest.files.contains_key(&e.path) && !files.iter().any(|(path, _)| path == &e.path));
+    engine.static_apis.retain(|api| manifest.files.contains_key(&api.path) && !files.iter().any(|(path, _)| path == &api.path));
+    engine.delegates.retain(|d| manifest.files.contains_key(&d.path) && !files.iter().any(|(path, _)| path == &d.path));
+    println!("{} of {} headers changed", files.iter().filter(|(_, dump)| *dump).count(), manifest.files.len());
+    let thread_count = settings.thread_count();
+    let step = files.len().div_ceil(thread_count).max(1);
     let mut threads = vec![];
     let mut engines = vec![];
-    for index in 0..files.len() / thread_count {
-        let mut local_files = Vec::with_capacity(step);
-        for i in index*step..(index+1)*step.min(files.len()) {
-            if i < files.len(){
-                local_files.push(files[i].clone());
-            }
-        }  
+    for local_files in files.chunks(step) {
+        let local_files = local_files.to_vec();
         let shared_engine = Arc::new(Mutex::new(Engine::default()));
         engines.push(shared_engine.clone());
         let target_dir = target_dir.clone();
-        let current_dir = current_dir.clone();
+        let code_dir = code_dir.clone();
+        let clang = clang.clone();
+        let clang_args = clang_args.clone();
+        let layout_args = layout_args.clone();
         threads.push(std::thread::spawn(move ||{
-            let block = || -> anyhow::Result<()>{
-                for file in &local_files {
+            //headers fail to dump
+            let mut failed = vec![];
+            //(relative path, included headers) of new dumps
+            let mut dependencies = vec![];
+            let mut block = || -> anyhow::Result<()>{
+                for (relative_path, need_dump) in &local_files {
                     // if !file.ends_with("GameplayStatics.h"){
                     //     continue;
                     // }
-                    // let file_name = Path::new(&file).file_stem().unwrap().to_str().unwrap();
-                    let file_path = current_dir.join(&file).display().to_string().replace("/", "\\");
-                    let target_file_path = file.replace("\\", "/").replace("engine_code/", "");
-                    let target_path = format!("{}\\{}.json", &target_dir, target_file_path);
-                    let out_file =if let Ok(c)= std::fs::read_to_string(&target_path){
-                        // println!("parse ast file {target_path}");
-                        c
-                    }
-                    else{
-                        let cmd = format!(
-                            "clang -Xclang -ast-dump=json -fsyntax-only -x c++ -std=c++17 {}",
-                            file_path//, target_path
-                        );
-                        // println!("thread {index} cmd {}", cmd);
-                        let output = std::process::Command::new("powershell")
-                        .arg("/c")
-                        .arg(&cmd)
+                    let file_path = code_dir.join(relative_path);
+                    let target_path = dump_path(&target_dir, relative_path);
+                    let out_file = if *need_dump{
+                        let depfile_path = depfile_path(&target_dir, relative_path);
+                        std::fs::create_dir_all(target_path.parent().unwrap()).ok();
+                        let output = std::process::Command::new(&clang)
+                        .args(&clang_args)
+                        .arg("-MD").arg("-MF").arg(&depfile_path)
+                        .arg(&file_path)
                         .output()?;
                         let out_file = unsafe{ String::from_utf8_unchecked(output.stdout)};
                         if !output.status.success() && out_file.is_empty(){
-                            println!("file {} cmd {} result {:?}", file_path, output.status, unsafe{ String::from_utf8_unchecked(output.stderr)});
+                            println!("file {} cmd {} result {:?}", file_path.display(), output.status, unsafe{ String::from_utf8_unchecked(output.stderr)});
+                            failed.push(relative_path.clone());
                             continue;
                         }
+                        match std::fs::read_to_string(&depfile_path) {
+                            Ok(depfile) => {
+                                dependencies.push((relative_path.clone(), manifest::hash_dependencies(&depfile)));
+                                std::fs::remove_file(&depfile_path).ok();
+                            }
+                            Err(e) => println!("file {} no depfile {:?}, include changes are not tracked", file_path.display(), e),
+                        }
                         out_file
-                    };                    
-                    let ast: Node = serde_json::from_str(&out_file)?;
-                    std::fs::create_dir_all(Path::new(&target_path).parent().unwrap()).ok();
-                    std::fs::write(target_path, out_file)?;
+                    }
+                    else{
+                        std::fs::read_to_string(&target_path)?
+                    };
+                    let ast: Node = match serde_json::from_str(&out_file) {
+                        Ok(ast) => ast,
+                        Err(e) => {
+                            println!("file {} invalid ast {:?}", file_path.display(), e);
+                            failed.push(relative_path.clone());
+                            continue;
+                        }
+                    };
+                    if *need_dump{
+                        std::fs::write(target_path, out_file)?;
+                    }
                     // println!("cmd ast {}", ast.id.to_string());
-                    if let Err(e) = parse_file(&ast, &file_path, &mut shared_engine.lock().unwrap()){
-                        println!("parse file {file_path} ast fail {:?}", e);
+                    if let Err(e) = parse_file(&ast, &file_path, relative_path, &mut shared_engine.lock().unwrap()){
+                        println!("parse file {} ast fail {:?}", file_path.display(), e);
                     } 
+                    if let Some(layout_args) = &layout_args{
+                        let layout_path = layout_path(&target_dir, relative_path);
+                        let out_file = if *need_dump{
+                            dump_record_layouts(&clang, layout_args, &file_path, &layout_path)
+                        }
+                        else{
+                            std::fs::read_to_string(&layout_path).map_err(anyhow::Error::from)
+                        };
+                        match out_file {
+                            Ok(out_file) => apply_record_layouts(&parse_record_layouts(&out_file), relative_path, &mut shared_engine.lock().unwrap()),
+                            Err(e) => {
+                                println!("file {} record layouts fail {e}", file_path.display());
+                                failed.push(relative_path.clone());
+                                continue;
+                            }
+                        }
+                    }
                     // break;
                 }
                 Ok(())
@@ -120,49 +203,239 @@ pub fn run(engine: &mut Engine) -> anyhow::Result<()>{
             if let Err(e) = block(){
                 println!("fail to parse fiels {:?}", e);
             }
+            (failed, dependencies)
         }));
     }
     for t in threads {
-        t.join().ok();
+        let (failed, dependencies) = t.join().unwrap_or_default();
+        for (relative_path, dependencies) in dependencies{
+            if let Some(entry) = manifest.files.get_mut(&relative_path){
+                entry.dependencies = dependencies;
+            }
+        }
+        //dump again next time
+        for failed in failed{
+            manifest.files.remove(&failed);
+        }
     }
     for e in engines{
         let mut et = e.lock().unwrap();
         engine.classes.append(&mut et.classes);
         engine.enums.append(&mut et.enums);
         engine.static_apis.append(&mut et.static_apis);
+        engine.delegates.append(&mut et.delegates);
+    }
+    Ok(manifest)
+}
+///clang arguments of the ast dump except the header
+fn clang_args(settings: &CustomSettings) -> Vec<String>{
+    ["-Xclang", "-ast-dump=json", "-fsyntax-only", "-x", "c++", "-std=c++17"]
+    .into_iter()
+    .map(|arg| arg.to_string())
+    .chain(settings.include_paths().iter().map(|path| format!("-I{}", crate::path_to_string(path))))
+    .chain(settings.Defines.iter().map(|define| format!("-D{define}")))
+    .chain(settings.ClangArgs.iter().cloned())
+    .collect()
+}
+///clang arguments of the record layouts dump except the header
+fn layout_args(clang_args: &[String]) -> Vec<String>{
+    clang_args.iter()
+    .map(|arg| if arg == "-ast-dump=json"{ "-fdump-record-layouts-complete".to_string() } else { arg.clone() })
+    .collect()
+}
+///first line of `clang --version`
+fn clang_version(clang: &str) -> anyhow::Result<String>{
+    let output = std::process::Command::new(clang)
+    .arg("--version")
+    .output()
+    .map_err(|e| anyhow::anyhow!("fail to run {clang}: {e}"))?;
+    Ok(String::from_utf8_lossy(&output.stdout).lines().next().unwrap_or_default().trim().to_string())
+}
+///sizeof(void*) of the clang target, 0 if clang does not report it
+fn pointer_size(clang: &str, settings: &CustomSettings) -> usize{
+    let Ok(output) = std::process::Command::new(clang)
+    .args(["-dM", "-E", "-x", "c++"])
+    .args(&settings.ClangArgs)
+    .arg("-")
+    .stdin(std::process::Stdio::null())
+    .output() else{
+        return 0;
+    };
+    let size = String::from_utf8_lossy(&output.stdout).lines()
+    .find_map(|line| line.strip_prefix("#define __SIZEOF_POINTER__ ")?.trim().parse().ok())
+    .unwrap_or_default();
+    if size == 0{
+        println!("pointer size of clang target is unknown, layout of structs with pointers is not checked");
+    }
+    size
+}
+///unreal_ast/<relative path>.json
+fn dump_path(target_dir: &Path, relative_path: &str) -> PathBuf{
+    let mut target_path = target_dir.join(relative_path).into_os_string();
+    target_path.push(".json");
+    PathBuf::from(target_path)
+}
+///unreal_ast/<relative path>.d, removed once read
+fn depfile_path(target_dir: &Path, relative_path: &str) -> PathBuf{
+    let mut target_path = target_dir.join(relative_path).into_os_string();
+    target_path.push(".d");
+    PathBuf::from(target_path)
+}
+///unreal_ast/<relative path>.layout
+///run clang with `-fdump-record-layouts-complete` and cache the output at layout_path, nothing is cached if clang fails
+fn dump_record_layouts(clang: &str, layout_args: &[String], file_path: &Path, layout_path: &Path) -> anyhow::Result<String>{
+    let output = std::process::Command::new(clang)
+    .args(layout_args)
+    .arg(file_path)
+    .output()?;
+    let out_file = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() && out_file.is_empty(){
+        anyhow::bail!("{} result {:?}", output.status, String::from_utf8_lossy(&output.stderr));
+    }
+    std::fs::write(layout_path, &out_file)?;
+    Ok(out_file)
+}
+fn layout_path(target_dir: &Path, relative_path: &str) -> PathBuf{
+    let mut target_path = target_dir.join(relative_path).into_os_string();
+    target_path.push(".layout");
+    PathBuf::from(target_path)
+}
+#[derive(Debug, Default)]
+struct RecordLayout{
+    size: usize,
+    align: usize,
+    ///byte offsets of direct fields, bit fields are not included
+    offsets: BTreeMap<String, usize>,
+}
+///records of `-fdump-record-layouts` output, key is the record name
+///```text
+///*** Dumping AST Record Layout
+///         0 | struct FPoint
+///         0 |   int32 A
+///         8 |   double B
+///           | [sizeof=16, dsize=16, align=8,
+///```
+fn parse_record_layouts(out: &str) -> BTreeMap<String, RecordLayout>{
+    let mut layouts = BTreeMap::new();
+    for block in out.split("*** Dumping AST Record Layout").skip(1) {
+        let mut lines = block.lines()
+        .filter_map(|line| line.split_once('|'))
+        .map(|(offset, decl)| (offset.trim(), decl));
+        let Some(name) = lines.next().and_then(|(_, decl)| decl.split_whitespace().last()) else{
+            continue;
+        };
+        let mut layout = RecordLayout::default();
+        let mut anonymous = 0;
+        for (offset, decl) in lines {
+            let decl = decl.trim_end();
+            if let Some(sizes) = decl.trim_start().strip_prefix('['){
+                for (key, value) in sizes.split(',').filter_map(|pair| pair.split_once('=')) {
+                    let value = value.trim_end_matches(']').parse().unwrap_or_default();
+                    match key.trim() {
+                        "sizeof" => layout.size = value,
+                        "align" => layout.align = value,
+                        _ => (),
+                    }
+                }
+                continue;
+            }
+            //direct fields are indented by 3 spaces, `(base)` and `(vtable pointer)` are not fields
+            let indent = decl.len() - decl.trim_start().len();
+            if indent != 3{
+                continue;
+            }
+            //anonymous members are named as `union0` `struct1` like parse_class does
+            if decl.contains("(anonymous"){
+                if let (Ok(offset), Some(tag)) = (offset.parse(), decl.split_whitespace().next()){
+                    layout.offsets.insert(format!("{tag}{anonymous}"), offset);
+                    anonymous += 1;
+                }
+                continue;
+            }
+            if decl.ends_with(')'){
+                continue;
+            }
+            if let (Ok(offset), Some(field)) = (offset.parse(), decl.split_whitespace().last()){
+                layout.offsets.insert(field.to_string(), offset);
+            }
+        }
+        if layout.size > 0{
+            layouts.insert(name.to_string(), layout);
+        }
+    }
+    layouts
+}
+///store record layouts on classes of the header
+fn apply_record_layouts(layouts: &BTreeMap<String, RecordLayout>, relative_path: &str, engine: &mut Engine){
+    for class in engine.classes.iter_mut().filter(|class| class.path == relative_path) {
+        let Some(layout) = layouts.get(&class.name) else{
+            continue;
+        };
+        class.size = layout.size;
+        class.align = layout.align;
+        for property in class.properties.iter_mut().chain(class.none_public_properties.iter_mut()) {
+            property.offset = if property.bit_value{ None } else { layout.offsets.get(&property.name).copied() };
+        }
     }
-    // let mut command = std::process::Command::new("powershell");
-    // command.arg("chcp 65001\r\n").output().ok();
-    Ok(())
 }
 #[derive(Debug, Default)]
 struct ParseState{
     is_pub: bool,
+    ///in protected section, virtual functions there can be overridden and call Super
+    is_protected: bool,
     is_struct: bool,
     is_class: bool,  
+    ///anonymous union and struct members of the parsed class
+    records: Vec<UnrealClass>,
     content: String,
     file_path: String,
+    ///reflection specifiers from markers of the stripped header, key is `Owner::Name` or `Name`
+    specifiers: BTreeMap<String, Specifiers>,
 }
 impl ParseState{
     fn clear(&mut self){
         self.is_class = false;
         self.is_struct = false;
         self.is_pub = false;   
+        self.is_protected = false;
     }
-}
-#[inline]
-fn get_file_path(fp: &str) -> String{
-    let mut cur = std::env::current_dir().unwrap().display().to_string().replace("\\", "/");
-    if !cur.ends_with("/"){
-        cur.push_str("/");
+    fn specifiers(&self, owner: Option<&str>, name: &str) -> Specifiers{
+        let key = owner.map(|owner| format!("{owner}::{name}")).unwrap_or(name.to_string());
+        self.specifiers.get(&key).cloned().unwrap_or_default()
     }
-    fp.replace("\\", "/").replace(&cur, "")
-    .replace("engine_code/", "")
+}
+///`//@UPROPERTY AActor::bHidden(BlueprintReadOnly)` markers appended by class_parser
+fn parse_reflection_markers(content: &str) -> BTreeMap<String, Specifiers>{
+    content.split("\r\n")
+    .filter_map(|line| line.strip_prefix("//@"))
+    .filter_map(|marker| marker.split_once(' '))
+    .filter(|(macro_name, _)| !macro_name.starts_with("DECLARE_"))
+    .filter_map(|(_, decl)| decl.split_once('('))
+    .map(|(key, args)| (key.to_string(), Specifiers::parse(args.strip_suffix(')').unwrap_or(args))))
+    .collect()
+}
+///`//@DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam FOnX(AActor*, Actor)` markers appended by class_parser
+fn parse_delegate_markers(content: &str) -> Vec<CppDelegate>{
+    content.split("\r\n")
+    .filter_map(|line| line.strip_prefix("//@"))
+    .filter_map(|marker| marker.split_once(' '))
+    .filter(|(macro_name, _)| macro_name.starts_with("DECLARE_"))
+    .filter_map(|(macro_name, decl)| {
+        let (name, args) = decl.split_once('(')?;
+        let delegate = CppDelegate::parse(macro_name, name, args.strip_suffix(')').unwrap_or(args));
+        if delegate.is_none(){
+            println!("fail to parse delegate {name}");
+        }
+        delegate
+    })
+    .collect()
 }
 ///parse file with ast
-fn parse_file(ast: &Node, file_path: &str, engine: &mut Engine) -> anyhow::Result<()>{
-    let mut state = ParseState::default();
-    state.content = std::fs::read_to_string(file_path)?;
+fn parse_file(ast: &Node, file_path: &Path, relative_path: &str, engine: &mut Engine) -> anyhow::Result<()>{
+    let mut state = ParseState{
+        content: std::fs::read_to_string(file_path)?,
+        ..Default::default()
+    };
     if state.content.is_empty(){
         return Ok(());
     }
@@ -170,7 +443,12 @@ fn parse_file(ast: &Node, file_path: &str, engine: &mut Engine) -> anyhow::Resul
     // if file_path.ends_with("GameplayStatics.h"){
     //     println!("pause");
     // }
-    state.file_path = get_file_path(file_path);
+    state.file_path = relative_path.to_string();
+    state.specifiers = parse_reflection_markers(&state.content);
+    for mut delegate in parse_delegate_markers(&state.content){
+        delegate.path = relative_path.to_string();
+        engine.delegates.push(delegate);
+    }
     parse_node(ast, engine, &mut state)?;
     Ok(())
 }
@@ -186,24 +464,15 @@ fn parse_node(ast: &Node, engine: &mut Engine, state: &mut ParseState) -> anyhow
                     match kind.tagUsed.as_str() {
                         "class" => {
                             state.is_class = true;
-                            if let Some(mut clas) = parse_class(&ast, state)?{
-                                clas.path = state.file_path.clone();
-                                clas.public_apis.sort_by_key(|api| api.name.clone());
-                                clas.public_apis.dedup_by(|a, b| {
-                                    a.name == b.name && a.parameters.len() == b.parameters.len()
-                                });
-                                engine.classes.push(clas);
+                            if let Some(clas) = parse_class(ast, &kind.name, state)?{
+                                push_class(clas, engine, state);
                             }
                         },
-                        "struct" => {
+                        "struct" | "union" => {
                             state.is_struct = true;
-                            if let Some(mut clas) = parse_class(&ast, state)?{
-                                clas.path = state.file_path.clone();
-                                clas.public_apis.sort_by_key(|api| api.name.clone());
-                                clas.public_apis.dedup_by(|a, b| {
-                                    a.name == b.name && a.parameters.len() == b.parameters.len()
-                                });
-                                engine.classes.push(clas);
+                            if let Some(mut clas) = parse_class(ast, &kind.name, state)?{
+                                clas.is_union = kind.tagUsed == "union";
+                                push_class(clas, engine, state);
                             }
                         }
                         _ => (),
@@ -212,21 +481,24 @@ fn parse_node(ast: &Node, engine: &mut Engine, state: &mut ParseState) -> anyhow
             }
         },
         clang_ast::Kind::NamespaceDecl if {
-            ast.inner.iter().next()
+            ast.inner.first()
             .map(|node| node.kind.kind == clang_ast::Kind::EnumDecl).unwrap_or_default()
         }=> {
-            if let Some(eu) = parse_enum(&ast.inner[0], state, Some(ast.kind.name.clone()))?{
+            if let Some(mut eu) = parse_enum(&ast.inner[0], state, Some(ast.kind.name.clone()))?{
+                eu.path = state.file_path.clone();
                 engine.enums.push(eu);
             }
         },
         clang_ast::Kind::EnumDecl => {
-            if let Some(eu) = parse_enum(ast, state, None)?{
+            if let Some(mut eu) = parse_enum(ast, state, None)?{
+                eu.path = state.file_path.clone();
                 engine.enums.push(eu);
             }
         },
         //global const
         clang_ast::Kind::FunctionDecl => {
-            if let Some(api) = parse_api(ast, state)?{
+            if let Some(mut api) = parse_api(ast, state)?{
+                api.path = state.file_path.clone();
                 engine.static_apis.push(api);
                 engine.static_apis.sort_by_key(|api| api.name.clone());
                 engine.static_apis.dedup_by(|a, b| {
@@ -244,16 +516,13 @@ fn parse_node(ast: &Node, engine: &mut Engine, state: &mut ParseState) -> anyhow
 }
 ///parse enum
 /// int enum only
-fn parse_enum(node: &Node, _state: &mut ParseState, name_override: Option<String>) -> anyhow::Result<Option<CppEnum>>{
+fn parse_enum(node: &Node, state: &mut ParseState, name_override: Option<String>) -> anyhow::Result<Option<CppEnum>>{
     let mut cenum= CppEnum{
         namespace_enum: name_override.is_some(),
         name: name_override.unwrap_or(node.kind.name.clone()),
         enum_class: node.kind.scopedEnumTag.as_ref().map(|tag| tag == "class").unwrap_or_default(),
         ..Default::default()
     };
-    if cenum.name == "ETeleportType"{
-        println!("pause");
-    }
     let mut value: i32 = 0;
     let namespace_enum = format!("{}::Type", cenum.name);
     for node in &node.inner {
@@ -271,9 +540,9 @@ fn parse_enum(node: &Node, _state: &mut ParseState, name_override: Option<String
             }
             let mut ec = CppEnumConstant{
                 name: node.kind.name.clone(),
-                value: value,
+                value,
             };
-            if let Some(expr) =node.inner.iter().next(){
+            if let Some(expr) =node.inner.first(){
                 if let clang_ast::Kind::ConstantExpr = expr.kind.kind{
                     if let serde_json::Value::String(vs) = &expr.kind.value{
                         if let Ok(v) = vs.parse::<i32>(){
@@ -295,36 +564,70 @@ fn parse_enum(node: &Node, _state: &mut ParseState, name_override: Option<String
             value += 1;
         }
     }
+    cenum.specifiers = state.specifiers(None, &cenum.name);
     Ok(Some(cenum))
 }
+///push parsed class with its anonymous records
+fn push_class(mut clas: UnrealClass, engine: &mut Engine, state: &mut ParseState){
+    clas.path = state.file_path.clone();
+    clas.public_apis.sort_by_key(|api| api.name.clone());
+    clas.public_apis.dedup_by(|a, b| {
+        a.name == b.name && a.parameters.len() == b.parameters.len()
+    });
+    engine.classes.push(clas);
+    for mut record in state.records.drain(..) {
+        record.path = state.file_path.clone();
+        engine.classes.push(record);
+    }
+}
 ///parse class
-fn parse_class(node: &Node, state: &mut ParseState) -> anyhow::Result<Option<UnrealClass>>{
+fn parse_class(node: &Node, name: &str, state: &mut ParseState) -> anyhow::Result<Option<UnrealClass>>{
     macro_rules! none_pub {
         ($is_pub: expr) => {
             if !$is_pub{ continue;}
         };
     }
     //empty class decl
-    if node.inner.len() == 0{
+    if node.inner.is_empty(){
         return Ok(None);
     }
     let mut class = UnrealClass{
         is_struct: state.is_struct,
-        name: node.kind.name.clone(),
+        name: name.to_string(),
+        specifiers: state.specifiers(None, name),
+        //only the first base shares address with the class, upcasts are plain pointer casts
+        inherit: node.kind.bases.first()
+            .filter(|base| base.access == "public" && !base.isVirtual)
+            .and_then(|base| base.r#type.as_ref()?.qualType.clone())
+            .unwrap_or_default(),
         ..Default::default()
     };
     state.is_pub = state.is_struct;
+    state.is_protected = false;
     for node in &node.inner {
         let kind = &node.kind; 
         match kind.kind {
             clang_ast::Kind::AccessSpecDecl => {
                 state.is_pub = kind.access == "public";
+                state.is_protected = kind.access == "protected";
             },
             clang_ast::Kind::CXXConstructorDecl |
             clang_ast::Kind::CXXMethodDecl => {
+                //protected virtual functions can be overridden by generated subclass, private ones can not call Super
+                if !state.is_pub && kind.kind == clang_ast::Kind::CXXMethodDecl{
+                    if !state.is_protected{
+                        continue;
+                    }
+                    if let Some(mut api) = parse_api(node, state)?.filter(|api| api.is_virtual || api.is_override){
+                        api.class_name = class.name.clone();
+                        class.virtual_apis.push(api);
+                    }
+                    continue;
+                }
                 none_pub!(state.is_pub);
                 if let Some(mut api) = parse_api(node, state)?{
                     api.class_name = class.name.clone();
+                    api.specifiers = state.specifiers(Some(&class.name), &api.name);
                     if let Some(exist)= class.public_apis.iter().find(|a| a.name == api.name){
                         if exist.parameters.len() != api.parameters.len(){
                             class.public_apis.push(api);
@@ -336,7 +639,22 @@ fn parse_class(node: &Node, state: &mut ParseState) -> anyhow::Result<Option<Unr
                 }
             },
             clang_ast::Kind::FieldDecl => {
-                let field = parse_field(kind, state)?;
+                //implicit member of anonymous record
+                if kind.name.is_empty(){
+                    continue;
+                }
+                let mut field = parse_field(kind, state)?;
+                //`union {...} Name;` the record can not be named in c++, but its member can
+                if field.type_str.contains("(unnamed"){
+                    let record = class.properties.iter_mut().chain(class.none_public_properties.iter_mut())
+                        .rfind(|property| property.anonymous);
+                    if let Some(record) = record{
+                        record.name = field.name;
+                        record.anonymous = false;
+                    }
+                    continue;
+                }
+                field.specifiers = state.specifiers(Some(&class.name), &field.name);
                 if state.is_pub{
                     class.properties.push(field);
                 }
@@ -344,34 +662,45 @@ fn parse_class(node: &Node, state: &mut ParseState) -> anyhow::Result<Option<Unr
                     class.none_public_properties.push(field);
                 }
             },
-            clang_ast::Kind::CXXRecordDecl => {
-                if kind.tagUsed == "union"{
-                    parse_union(node, &mut class, state)?;
+            //anonymous union or struct, its members are accessed through the owner in c++
+            clang_ast::Kind::CXXRecordDecl if kind.name.is_empty() && matches!(kind.tagUsed.as_str(), "union" | "struct") => {
+                let properties = class.properties.iter().chain(class.none_public_properties.iter());
+                let (records, anonymous) = properties.fold((0, 0), |(records, anonymous), property| {
+                    (records + property.type_str.starts_with(&format!("{}_", class.name)) as usize, anonymous + property.anonymous as usize)
+                });
+                let tag = if kind.tagUsed == "union" {"Union"} else {"Struct"};
+                let (field, record) = (format!("{}{anonymous}", kind.tagUsed), format!("{}_{tag}{records}", class.name));
+                let (is_pub, is_protected, is_struct) = (state.is_pub, state.is_protected, state.is_struct);
+                state.is_struct = true;
+                let parsed = parse_class(node, &record, state)?;
+                (state.is_pub, state.is_protected, state.is_struct) = (is_pub, is_protected, is_struct);
+                let Some(mut parsed) = parsed else{
+                    continue;
+                };
+                parsed.is_union = kind.tagUsed == "union";
+                parsed.is_unnamed = true;
+                let (r_type, value_type) = super::parse_c_type(&record);
+                let field = CppProperty{
+                    name: field,
+                    type_str: record,
+                    r_type,
+                    value_type: value_type as i32,
+                    anonymous: true,
+                    ..Default::default()
+                };
+                if state.is_pub{
+                    class.properties.push(field);
+                }
+                else{
+                    class.none_public_properties.push(field);
                 }
+                state.records.push(parsed);
             }
             _ => (),
         }
     }
     Ok(Some(class))
 }
-//parse union to property at this moment
-fn parse_union(node: &Node, class: &mut UnrealClass, state: &mut ParseState) -> anyhow::Result<()>{
-    //only struct is supported
-    if !class.is_struct{
-        return Ok(());
-    }
-    //parse first stuct
-    for child in &node.inner {
-        if child.kind.kind == clang_ast::Kind::CXXRecordDecl && child.kind.tagUsed == "struct"{
-            if let Some(mut structure) = parse_class(child, state)?{
-                class.properties.append(&mut structure.properties);
-                class.none_public_properties.append(&mut structure.none_public_properties);
-                break;
-            }
-        }
-    }
-    Ok(())
-}
 ///parse api
 fn parse_api(node: &Node, state: &ParseState) -> anyhow::Result<Option<CppApi>>{
     let kind = &node.kind;
@@ -384,6 +713,7 @@ fn parse_api(node: &Node, state: &ParseState) -> anyhow::Result<Option<CppApi>>{
         ..Default::default()
     };
     parse_parm_decl(&node.inner, &mut api, state)?;
+    api.is_override = node.inner.iter().any(|inner| inner.kind.kind == clang_ast::Kind::OverrideAttr);
     if let Some(sc)= &kind.storageClass{
         if sc == "static"{
             api.is_static = true;
@@ -431,11 +761,11 @@ fn parse_api(node: &Node, state: &ParseState) -> anyhow::Result<Option<CppApi>>{
         api.rc_type = api.rc_type[index + 5..].trim().to_string();
         api.const_ret = true;
     }
-    if let Some(index) = api.rc_type.find("&"){
+    if let Some(index) = find_type_tag(&api.rc_type, "&"){
         api.rc_type = api.rc_type[..index].trim().to_string();
         api.ref_ret = true;
     }
-    else if let Some(index) = api.rc_type.find("*"){
+    else if let Some(index) = find_type_tag(&api.rc_type, "*"){
         api.rc_type = api.rc_type[..index].trim().to_string();
         api.ptr_ret = true;
     }
@@ -445,6 +775,7 @@ fn parse_api(node: &Node, state: &ParseState) -> anyhow::Result<Option<CppApi>>{
             api.is_generic = true;
         }
     }
+    api.ret_container = Container::parse(&api.rc_type);
     let (r_s, vt) = super::parse_c_type(&api.rc_type);
     api.r_type = r_s;
     if api.r_type == "T" || api.rc_type == "T"{
@@ -468,6 +799,14 @@ fn is_bit_value(state: &ParseState, mut offset: usize) -> bool{
     }
     false
 }
+///width of bit field `uint8 bFoo : 1;`, 0 if it is not a literal
+fn bit_width(state: &ParseState, offset: usize) -> usize{
+    state.content.get(offset..)
+    .and_then(|content| content.split_once(';'))
+    .and_then(|(decl, _)| decl.split_once(':'))
+    .and_then(|(_, width)| width.trim().parse().ok())
+    .unwrap_or_default()
+}
 ///parse field
 fn parse_field(kind: &Clang, state: &ParseState) -> anyhow::Result<CppProperty>{    
     let mut field = CppProperty{
@@ -482,19 +821,32 @@ fn parse_field(kind: &Clang, state: &ParseState) -> anyhow::Result<CppProperty>{
     else{
         field.type_str = kind.r#type.as_ref().map(|qual| qual.qualType.clone().unwrap_or_default()).unwrap_or_default();        
     }
-    if let Some(index) = field.type_str.find("*"){
+    //static array `float [4]`
+    if let Some((element, dim)) = field.type_str.strip_suffix(']').and_then(|type_str| type_str.rsplit_once('[')){
+        match dim.trim().parse() {
+            Ok(dim) if !element.contains('[') => {
+                field.array_dim = dim;
+                field.type_str = element.trim().to_string();
+            },
+            //multi dimensional or not literal
+            _ => field.unsupported = true,
+        }
+    }
+    if let Some(index) = find_type_tag(&field.type_str, "*"){
         field.type_str = field.type_str[..index].trim().to_string();
         field.is_ptr = true;
     }
     if let (Some(_), Some(_)) = (field.type_str.find("<"), field.type_str.find(">")){
         field.is_generic = true;
+        field.container = Container::parse(&field.type_str);
     }
-    field.bit_value = is_bit_value(
-        state, 
-        kind.loc.as_ref()
+    let offset = kind.loc.as_ref()
         .map(|loc| loc.expansion_loc.as_ref().map(|loc| loc.offset))
-        .unwrap_or_default().unwrap_or_default()
-    );
+        .unwrap_or_default().unwrap_or_default();
+    field.bit_value = is_bit_value(state, offset);
+    if field.bit_value{
+        field.bit_width = bit_width(state, offset);
+    }
     let (r_s, vt) = super::parse_c_type(&field.type_str);
     field.r_type = r_s;
     field.value_type = vt as i32;
@@ -543,12 +895,11 @@ fn parse_parm_decl(inner: &Vec<Node>, api: &mut CppApi, state: &ParseState) -> a
                 if param.type_str.starts_with("const"){
                     if let Some(index) = param.type_str.find("const"){
                         let next = index + 5;
-                        if next < param.type_str.len(){
-                            if is_space(&param.type_str[next..=next]){                            
+                        if next < param.type_str.len()
+                            && is_space(&param.type_str[next..=next]){                            
                                 param.type_str = param.type_str[next..].trim().to_string();
                                 param.const_param = true;
                             }
-                        }
                     }
                 }
                 if param.type_str.ends_with("const"){
@@ -582,15 +933,15 @@ fn parse_parm_decl(inner: &Vec<Node>, api: &mut CppApi, state: &ParseState) -> a
                 if param.type_str.contains("struct ") || param.type_str.contains("struct\t"){
                     param.type_str = param.type_str.replace("struct", "").trim().to_string();
                 }
-                if let Some(index) = param.type_str.find("&&"){
+                if let Some(index) = find_type_tag(&param.type_str, "&&"){
                     param.type_str = param.type_str[..index].trim().to_string();
                     param.move_param = true;
                 }
-                else if let Some(index) = param.type_str.find("&"){
+                else if let Some(index) = find_type_tag(&param.type_str, "&"){
                     param.type_str = param.type_str[..index].trim().to_string();
                     param.ref_param = true;
                 }
-                else if let Some(index) = param.type_str.find("*"){
+                else if let Some(index) = find_type_tag(&param.type_str, "*"){
                     param.type_str = param.type_str[..index].trim().to_string();
                     param.ptr_param = true;
                 }
@@ -598,7 +949,7 @@ fn parse_parm_decl(inner: &Vec<Node>, api: &mut CppApi, state: &ParseState) -> a
                     param.is_generic = true;
                 }
                 //some api can not split type and name
-                let mut types = param.type_str.split(|c| c == ' ' || c == '\t').map(|s| s.to_string()).collect::<Vec<_>>();
+                let mut types = param.type_str.split([' ', '\t']).map(|s| s.to_string()).collect::<Vec<_>>();
                 if types.len() > 1{
                     let name = types.remove(types.len() - 1).trim().to_string();
                     //name in type_str 
@@ -606,6 +957,9 @@ fn parse_parm_decl(inner: &Vec<Node>, api: &mut CppApi, state: &ParseState) -> a
                         param.type_str = types.join(" ").trim().to_string();
                     }
                 }
+                if param.is_generic{
+                    param.container = Container::parse(&param.type_str);
+                }
                 let (r_s, vt) = super::parse_c_type(&param.type_str);
                 param.r_type = r_s;
                 if param.type_str == "T" || param.r_type == "T"{
@@ -629,7 +983,171 @@ fn parse_parm_decl(inner: &Vec<Node>, api: &mut CppApi, state: &ParseState) -> a
     api.parameters = params;
     Ok(())
 }
+///find ptr/ref tag out of template arguments, `TArray<AActor*>&` is a ref of TArray
+fn find_type_tag(type_str: &str, tag: &str) -> Option<usize>{
+    let start = type_str.rfind('>').map(|index| index + 1).unwrap_or_default();
+    type_str[start..].find(tag).map(|index| index + start)
+}
 #[inline]
 fn is_space(index: &str) -> bool {
     index == " " || index == "\t"
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    ///CXXMethodDecl json of `decl` in `content`, clang_ast expects `id` and `kind` first
+    fn method_json(content: &str, name: &str, decl: &str) -> String{
+        let start = content.find(decl).unwrap();
+        let loc = |offset: usize| format!(r#"{{"offset": {offset}, "file": "Foo.h", "line": 1, "col": 1, "tokLen": 1}}"#);
+        format!(r#"{{"id": "0x2", "kind": "CXXMethodDecl", "name": "{name}", "loc": {}, "range": {{"begin": {}, "end": {}}}, "type": {{"qualType": "void ()"}}}}"#,
+            loc(start + decl.find(name).unwrap()), loc(start), loc(start + decl.len()))
+    }
+    #[test]
+    fn only_protected_virtuals_can_be_overridden() {
+        let content = "class AFoo\n{\nprotected:\n\tvirtual void Tick();\n\tvoid Helper();\nprivate:\n\tvirtual void Hidden();\npublic:\n\tvirtual void Open();\n};";
+        let access = |access: &str| format!(r#"{{"id": "0x3", "kind": "AccessSpecDecl", "access": "{access}"}}"#);
+        let inner = [
+            access("protected"),
+            method_json(content, "Tick", "virtual void Tick();"),
+            method_json(content, "Helper", "void Helper();"),
+            access("private"),
+            method_json(content, "Hidden", "virtual void Hidden();"),
+            access("public"),
+            method_json(content, "Open", "virtual void Open();"),
+        ];
+        let record = format!(r#"{{"id": "0x1", "kind": "CXXRecordDecl", "name": "AFoo", "tagUsed": "class", "inner": [{}]}}"#, inner.join(", "));
+        let node: Node = serde_json::from_str(&record).unwrap();
+        let mut state = ParseState{ content: content.to_string(), ..Default::default() };
+        let class = parse_class(&node, "AFoo", &mut state).unwrap().unwrap();
+        assert_eq!(class.virtual_apis.iter().map(|api| api.name.as_str()).collect::<Vec<_>>(), vec!["Tick"]);
+        assert_eq!(class.public_apis.iter().map(|api| api.name.as_str()).collect::<Vec<_>>(), vec!["Open"]);
+    }
+    #[test]
+    fn named_fields_of_unnamed_records() {
+        let field = |name: &str, qual_type: &str| format!(r#"{{"id": "0x4", "kind": "FieldDecl", "name": "{name}", "type": {{"qualType": "{qual_type}"}}}}"#);
+        let record = |tag: &str, inner: &str| format!(r#"{{"id": "0x3", "kind": "CXXRecordDecl", "tagUsed": "{tag}", "inner": [{inner}]}}"#);
+        let inner = [
+            record("struct", &field("W", "float")),
+            field("Box", "struct (unnamed struct at Boxed.h:3:2)"),
+            record("union", &[field("I", "int32"), field("F", "float")].join(", ")),
+            field("", "union (anonymous union at Boxed.h:7:2)"),
+        ];
+        let node: Node = serde_json::from_str(&format!(r#"{{"id": "0x1", "kind": "CXXRecordDecl", "name": "FBoxed", "tagUsed": "struct", "inner": [{}]}}"#, inner.join(", "))).unwrap();
+        let mut state = ParseState{ is_struct: true, ..Default::default() };
+        let class = parse_class(&node, "FBoxed", &mut state).unwrap().unwrap();
+        let fields = class.properties.iter().map(|p| (p.name.as_str(), p.type_str.as_str(), p.anonymous)).collect::<Vec<_>>();
+        assert_eq!(fields, vec![("Box", "FBoxed_Struct0", false), ("union0", "FBoxed_Union1", true)]);
+        assert!(state.records.iter().all(|record| record.is_unnamed));
+        assert_eq!(state.records.iter().map(|record| record.name.as_str()).collect::<Vec<_>>(), vec!["FBoxed_Struct0", "FBoxed_Union1"]);
+    }
+    #[test]
+    fn defines_select_the_branch_clang_sees() {
+        let header = [
+            "#pragma once",
+            "#include \"CoreMinimal.h\"",
+            "#include \"Foo.generated.h\"",
+            "#if WITH_EDITOR",
+            "struct FEditorOnly",
+            "{",
+            "    int32 Value;",
+            "};",
+            "#else",
+            "struct FRuntimeOnly",
+            "{",
+            "    int32 Value;",
+            "};",
+            "#endif",
+        ];
+        let stripped = super::super::class_parser::strip_header(header.iter().map(|line| line.to_string()).collect());
+        assert!(stripped.iter().any(|line| line == "#if WITH_EDITOR"));
+        assert!(stripped.iter().any(|line| line == "#if __has_include(\"CoreMinimal.h\")"));
+        assert!(!stripped.iter().any(|line| line.contains(".generated.h")));
+        //preprocess the stripped header with the defines given to clang
+        let preprocess = |define: &str| -> Option<String>{
+            let settings = CustomSettings{ Defines: vec![define.to_string()], ..Default::default() };
+            let defines = clang_args(&settings).into_iter().filter(|arg| arg.starts_with("-D")).collect::<Vec<_>>();
+            let mut child = std::process::Command::new(settings.clang_path())
+            .args(["-E", "-P", "-x", "c++"])
+            .args(defines)
+            .arg("-")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .ok()?;
+            child.stdin.take()?.write_all(stripped.join("\r\n").as_bytes()).ok()?;
+            let output = child.wait_with_output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        };
+        let Some(editor) = preprocess("WITH_EDITOR=1") else{
+            println!("clang not found, skipped");
+            return;
+        };
+        assert!(editor.contains("FEditorOnly") && !editor.contains("FRuntimeOnly"));
+        let runtime = preprocess("WITH_EDITOR=0").unwrap();
+        assert!(runtime.contains("FRuntimeOnly") && !runtime.contains("FEditorOnly"));
+    }
+    #[test]
+    fn type_tag_of_plain_types() {
+        assert_eq!(find_type_tag("AActor *", "*"), Some(7));
+        assert_eq!(find_type_tag("const FHitResult &", "&"), Some(17));
+        assert_eq!(find_type_tag("FVector &&", "&&"), Some(8));
+        assert_eq!(find_type_tag("int32", "*"), None);
+    }
+    #[test]
+    fn type_tag_out_of_template_arguments() {
+        assert_eq!(find_type_tag("TArray<AActor *>", "*"), None);
+        assert_eq!(find_type_tag("TArray<AActor *> &", "&"), Some(17));
+        assert_eq!(find_type_tag("TMap<FName, TArray<UObject *>> *", "*"), Some(31));
+        assert_eq!(find_type_tag("TMap<FName, TArray<UObject *>>", "*"), None);
+    }
+    #[test]
+    fn record_layouts_of_direct_fields() {
+        //clang 16 -Xclang -fdump-record-layouts-complete
+        let out = r#"
+*** Dumping AST Record Layout
+         0 | struct FInner
+         0 |   float X
+         4 |   float Y
+           | [sizeof=8, dsize=8, align=4,
+           |  nvsize=8, nvalign=4]
+
+*** Dumping AST Record Layout
+         0 | struct FOuter
+         0 |   int32 Count
+         4 |   struct FInner Inner
+         4 |     float X
+         8 |     float Y
+        12 |   union FOuter::(anonymous at Outer.h:8:5)
+        12 |     int32 I
+        12 |     float F
+    16:0-0 |   uint8 bEnabled
+    16:1-3 |   uint8 Mode
+        20 |   struct (unnamed struct at Outer.h:14:5) Box
+        20 |     float W
+        24 |     float H
+        32 |   double Weight
+           | [sizeof=40, dsize=40, align=8,
+           |  nvsize=40, nvalign=8]
+
+*** Dumping AST Record Layout
+         0 | struct FDerived
+         0 |   struct FInner (base)
+         0 |     float X
+         4 |     float Y
+         8 |   int32 Z
+           | [sizeof=12, dsize=12, align=4,
+           |  nvsize=12, nvalign=4]
+"#;
+        let layouts = parse_record_layouts(out);
+        let offsets = |name: &str| layouts[name].offsets.iter().map(|(field, offset)| (field.as_str(), *offset)).collect::<Vec<_>>();
+        assert_eq!((layouts["FInner"].size, layouts["FInner"].align), (8, 4));
+        assert_eq!(offsets("FInner"), vec![("X", 0), ("Y", 4)]);
+        //fields of nested records and bit fields are not direct byte offsets
+        assert_eq!((layouts["FOuter"].size, layouts["FOuter"].align), (40, 8));
+        assert_eq!(offsets("FOuter"), vec![("Box", 20), ("Count", 0), ("Inner", 4), ("Weight", 32), ("union0", 12)]);
+        //bases are not fields
+        assert_eq!(offsets("FDerived"), vec![("Z", 8)]);
+    }
+}
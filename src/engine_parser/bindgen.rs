@@ -1,6 +1,7 @@
-use std::{vec, collections::BTreeMap, sync::Mutex};
+use std::{vec, collections::BTreeMap, sync::Mutex, path::Path};
 
 use super::{
+    SmartPtr,
     unreal_engine::{
         Engine, 
         UnrealClass
@@ -8,7 +9,9 @@ use super::{
     config::{
         CustomSettings, 
         CppProperty, 
-        CppApi, CppEnum
+        CppApi, CppEnum, Specifiers,
+        Container, ContainerKind, CppDelegate,
+        TypeWrapper, WrapperField
     }
 };
 fn is_rs_primary(rs_type: &str, settings: &CustomSettings) -> bool{
@@ -37,16 +40,49 @@ struct CodeGenerator{
     include: Vec<String>,
     header: Vec<String>,
     source: Vec<String>,
+    ///To{rs_type}/To{cpp_type} of mapped wrappers, defined before `extern "C"` as they are c++ functions
+    converters: Vec<String>,
     registers: Vec<String>,
-    api_defines: Vec<String>,
+    ///native function table, cpp function name and rust function pointer type
+    bindings: Vec<(String, String)>,
+    ///layouts of structs passed by value, hashed into abi version with bindings
+    layouts: Vec<String>,
+    ///generated methods return Result<T, BindingError> instead of panic if native function is missing
+    checked_bindings: bool,
     rs_source: Vec<String>,
     rs_enums: Vec<String>,
-    rs_ffis: Vec<String>,
     type_impl: Vec<TypeImpl>,
+    ///delegates with generated param forwarder
+    forwarded_delegates: Vec<String>,
+    ///includes and class declarations of RustOverrides.h
+    override_includes: Vec<String>,
+    override_classes: Vec<String>,
     default_rs_header: usize,
     default_source_header: usize,
 }
 impl CodeGenerator{    
+    ///add cpp function into native function table, returns rust expression of the function pointer
+    pub fn push_binding(&mut self, cpp_name: &str, rs_fn_type: String) -> String{
+        self.bindings.push((cpp_name.to_string(), rs_fn_type));
+        if self.checked_bindings{
+            format!("binding(|bindings| bindings.{cpp_name}, \"{cpp_name}\")?")
+        }
+        else{
+            format!("bindings().{cpp_name}.unwrap()")
+        }
+    }
+    ///result of generated method, ret is ` -> T` or empty
+    pub fn rs_ret(&self, ret: &str) -> String{
+        if !self.checked_bindings{
+            return ret.to_string();
+        }
+        let ret = ret.trim_start_matches(" -> ");
+        format!(" -> Result<{}, BindingError>", if ret.is_empty(){ "()" } else { ret })
+    }
+    ///result expression of generated method
+    pub fn rs_ok(&self, expr: &str) -> String{
+        if self.checked_bindings{ format!("Ok({expr})") } else { expr.to_string() }
+    }
     pub fn insert_rs_type(&mut self, type_str: &str, engine: &Engine, settings: &CustomSettings) -> TypeImpl{
         if type_str == "()"{
             return  TypeImpl{
@@ -70,7 +106,7 @@ impl CodeGenerator{
                 ..Default::default()
             };
         }        
-        return self.insert_type(type_str, engine, settings);
+        self.insert_type(type_str, engine, settings)
     }
     ///insert opaque type
     pub fn insert_type(&mut self, type_str: &str, engine: &Engine, settings: &CustomSettings) -> TypeImpl{
@@ -123,7 +159,7 @@ impl Default for CodeGenerator{
             "#![allow(dead_code)]".to_string(),
             "#![allow(unused_imports)]".to_string(),
             "use super::*;".into(),
-            "use std::{ffi::{c_void, CString}, os::raw::c_char, ops::{Deref, DerefMut}};".to_string(),
+            "use std::{ffi::{c_void, CString}, os::raw::c_char, ops::{Deref, DerefMut}, collections::{HashMap, HashSet}};".to_string(),
             "use ffis::*;".to_string(),
             r#"pub struct RefResult<T: Sized>{
     t: T,
@@ -157,6 +193,7 @@ impl<T: Sized> DerefMut for RefResult<T> {
             default_source_header: 2,
             source,
             header: vec![],
+            converters: vec![],
             default_rs_header: rs_source.len(),
             rs_source,
             rs_enums: vec!["#![allow(non_camel_case_types)]".to_string()],
@@ -164,15 +201,19 @@ impl<T: Sized> DerefMut for RefResult<T> {
                 "\r\n\tauto const api_create_native_string = (create_native_string_handler)plugin->GetDllExport(TEXT(\"create_native_string\\0\"));".to_string(),
                 "\tif(api_create_native_string){ create_native_string = api_create_native_string; }".to_string(),
                 "\t\n\tauto const api_reset_rust_string = (reset_rust_string_handler)plugin->GetDllExport(TEXT(\"reset_rust_string\\0\"));".to_string(),
-                "\tif(api_reset_rust_string){ reset_rust_string = api_reset_rust_string; }".to_string(),                
+                "\tif(api_reset_rust_string){ reset_rust_string = api_reset_rust_string; }".to_string(),
             ],
-            api_defines: vec![
+            bindings: vec![
+                ("uapi_IsA".to_string(), "unsafe extern \"C\" fn(target: *mut c_void, uclass: *mut c_void) -> bool".to_string()),
+                ("uapi_GetWeakObject".to_string(), "unsafe extern \"C\" fn(ptr: WeakObjectPtr<c_void>) -> *mut c_void".to_string()),
+                ("uapi_MakeWeakObject".to_string(), "unsafe extern \"C\" fn(target: *mut c_void) -> WeakObjectPtr<c_void>".to_string()),
             ],
+            layouts: vec![],
+            checked_bindings: false,
             type_impl: vec![],
-            rs_ffis: vec![
-                "mod ffis{".to_string(),
-                "\tuse super::*;".to_string(),
-                ],
+            forwarded_delegates: vec![],
+            override_includes: vec![],
+            override_classes: vec![],
         }
     }
 }
@@ -198,19 +239,27 @@ pub fn should_export_api(engine: &Engine, api: &CppApi) -> bool{
         let mut types = vec![api.rc_type.as_str()];
         api.parameters.iter().for_each(|param| types.push(param.type_str.as_str()));
         for tsr in &types {
-            if !crate::is_primary(tsr) && !is_string_type(tsr) {
-                if engine.classes.iter().find(|class| class.name.as_str() == *tsr).is_none() &&
-                    engine.enums.iter().find(|eu| eu.equal(&tsr)).is_none(){
-                    return false
-                }
+            if !crate::is_primary(tsr) && !is_string_type(tsr) &&
+                engine.classes.iter().find(|class| class.name.as_str() == *tsr).is_none() &&
+                engine.enums.iter().find(|eu| eu.equal(tsr)).is_none(){
+                return false
             }
         }
         true
     }
 }
-pub fn generate(engine: &Engine, settings: &CustomSettings) -> anyhow::Result<()>{
-    let mut generator = CodeGenerator::default();
-    let default_header = std::fs::read_to_string("Binders/header.h")?;
+pub fn generate(engine: &mut Engine, settings: &CustomSettings) -> anyhow::Result<()>{
+    let mut generator = CodeGenerator{
+        checked_bindings: settings.CheckedBindings,
+        ..Default::default()
+    };
+    let default_header = std::fs::read_to_string(Path::new("Binders").join("header.h"))?;
+    let wrapper_source = std::fs::read_to_string(Path::new("Binders").join("wrapper.rs")).unwrap_or_default();
+    engine.wrapper_layouts = wrapper_layouts(&wrapper_source, engine.pointer_size, settings);
+    let engine = &*engine;
+    report_unknown_layouts(engine, settings);
+    gen_wrapper_layout_checks(engine, &mut generator, settings);
+    gen_wrappers(engine, &mut generator, settings)?;
     for class in &settings.ExportClasses{
         if let Some(engine_class) = engine.classes.iter().find(|cls| cls.name == class.class_name){
             gen_class(engine, engine_class, &mut generator, settings)?;
@@ -226,25 +275,35 @@ pub fn generate(engine: &Engine, settings: &CustomSettings) -> anyhow::Result<()
         }
     }
     for enum_def in &settings.ExportEnums{
-        if let Some(uenum) = engine.enums.iter().find(|e| e.equal(&enum_def)){
+        if let Some(uenum) = engine.enums.iter().find(|e| e.equal(enum_def)){
             export_enums(&mut generator, uenum)?;
         }
         else{
             println!("fail to find enum {}", enum_def);
         }
     }
-    generator.rs_ffis.push("}".to_string());
     //ffi apis
-    generator.rs_source.append(&mut generator.rs_ffis);
+    generator.layouts.push(format!("Real = {}", settings.real_type()));
+    let abi_hash = abi_hash(&generator);
+    generator.rs_source.push(gen_rs_bindings(&generator.bindings, abi_hash));
     generator.source.push("}".into());
     insert_cpp_wrappers(&mut generator)?;
     generator.source.insert(generator.default_source_header, generator.include.join("\r\n"));
-    // let api_defines = generator.api_defines.join("\r\n");
+    let (cpp_bindings, cpp_binding_values) = gen_cpp_bindings(&generator.bindings);
     let api_registers = format!(r#"
+//hash of native function table and struct layouts, should be the same as unreal_abi_hash of binders.rs
+constexpr uint64 UnrealAbiHash = 0x{abi_hash:016x}ull;
+using unreal_abi_hash_handler = uint64(*)();
 void register_all(Plugin* plugin){{
+    auto const api_unreal_abi_hash = (unreal_abi_hash_handler)plugin->GetDllExport(TEXT("unreal_abi_hash\0"));
+    if(!api_unreal_abi_hash || api_unreal_abi_hash() != UnrealAbiHash){{
+        UE_LOG(LogTemp, Error, TEXT("rust binders abi %llx is not the same as Binder.cpp abi %llx, regenerate binders"), api_unreal_abi_hash ? api_unreal_abi_hash() : 0, UnrealAbiHash);
+        return;
+    }}
     {}
+    {cpp_binding_values}
 }}"#, generator.registers.join("\r\n"));
-    insert_rs_wrappers(&mut generator)?;
+    insert_rs_wrappers(&mut generator, settings)?;
     let opaque_type_defines = generator.type_impl
     .iter()
     .map(|t| format!("\tpub type {} = c_void;//cpp type {}", t.alis, t.name))
@@ -258,20 +317,117 @@ void register_all(Plugin* plugin){{
 pub use opaque_types::*;
 "#)
     );
-    //fist insert ffi apis
-    generator.source.append(&mut generator.api_defines);
+    //fist insert native function table
+    generator.source.push(cpp_bindings);
     //last insert register files
     generator.source.push(api_registers);
-    std::fs::create_dir_all("binders/cpp/").ok();
-    std::fs::create_dir_all("binders/rs/").ok();
-    std::fs::write("binders/cpp/Binder.h", default_header + "\r\n" + &generator.header.join("\r\n"))?;
-    std::fs::write("binders/cpp/Binder.cpp", generator.source.join("\r\n"))?;
-    // std::fs::write("binders/cpp/FFI.h", api_defines)?;
-    // std::fs::write("binders/cpp/Registers.h", api_registers)?;
-    std::fs::write("binders/rs/binders.rs", generator.rs_source.join("\r\n"))?;
-    std::fs::write("binders/rs/enums.rs", generator.rs_enums.join("\r\n"))?;
+    let cpp_dir = settings.output_dir().join("cpp");
+    let rs_dir = settings.output_dir().join("rs");
+    std::fs::create_dir_all(&cpp_dir).ok();
+    std::fs::create_dir_all(&rs_dir).ok();
+    std::fs::write(cpp_dir.join("Binder.h"), insert_cpp_real(&default_header, settings) + "\r\n" + &generator.header.join("\r\n"))?;
+    std::fs::write(cpp_dir.join("Binder.cpp"), generator.source.join("\r\n"))?;
+    //uht header of rust override subclasses
+    if !generator.override_classes.is_empty(){
+        std::fs::write(cpp_dir.join("RustOverrides.h"), format!("#pragma once\r\n#include \"CoreMinimal.h\"\r\n{}\r\n#include \"RustOverrides.generated.h\"\r\n{}", 
+            generator.override_includes.join("\r\n"), generator.override_classes.join("\r\n")))?;
+    }
+    else{
+        std::fs::remove_file(cpp_dir.join("RustOverrides.h")).ok();
+    }
+    //uht header of delegate trampoline
+    if let Ok(delegate) = std::fs::read_to_string(Path::new("Binders").join("delegate.h")){
+        std::fs::write(cpp_dir.join("RustDelegate.h"), delegate)?;
+    }
+    std::fs::write(rs_dir.join("binders.rs"), generator.rs_source.join("\r\n"))?;
+    std::fs::write(rs_dir.join("enums.rs"), generator.rs_enums.join("\r\n"))?;
     Ok(())
 }
+///hash of all emitted function signatures and struct layouts
+fn abi_hash(generator: &CodeGenerator) -> u64{
+    let mut abi = String::new();
+    for (name, fn_type) in &generator.bindings{
+        abi += &format!("{name}: {fn_type}\n");
+    }
+    for layout in &generator.layouts{
+        abi += &format!("{layout}\n");
+    }
+    xxhash_rust::xxh3::xxh3_64(abi.as_bytes())
+}
+///rust side of native function table, copied once from register_all of Binder.cpp,
+///unchecked bindings refuse a table with missing functions as generated methods would panic
+fn gen_rs_bindings(bindings: &[(String, String)], abi_hash: u64) -> String{
+    let fields = bindings.iter().map(|(name, fn_type)| format!("\r\n\t\tpub {name}: Option<{fn_type}>,")).collect::<String>();
+    let names = bindings.iter().map(|(name, _)| format!("\r\n        \"{name}\",")).collect::<String>();
+    let count = bindings.len();
+    format!(r#"mod ffis{{
+    use super::*;
+    ///same layout as UnrealBindings of Binder.cpp
+    #[repr(C)]
+    pub struct UnrealBindings{{{fields}
+    }}
+    ///native functions of the table
+    pub const BINDING_NAMES: [&str; {count}] = [{names}
+    ];
+    ///hash of native function table and struct layouts, checked by register_all of Binder.cpp
+    pub const UNREAL_ABI_HASH: u64 = 0x{abi_hash:016x};
+    #[no_mangle]
+    extern "C" fn unreal_abi_hash() -> u64{{
+        UNREAL_ABI_HASH
+    }}
+    static BINDINGS: std::sync::OnceLock<UnrealBindings> = std::sync::OnceLock::new();
+    ///panics if bindings are not registered by register_all of Binder.cpp,
+    ///callbacks from native side catch the panic
+    #[inline]
+    pub fn bindings() -> &'static UnrealBindings{{
+        BINDINGS.get().expect("unreal bindings are not registered")
+    }}
+    #[inline]
+    pub fn try_bindings() -> Option<&'static UnrealBindings>{{
+        BINDINGS.get()
+    }}
+    ///native function of the table, BindingError if it is not registered
+    #[inline]
+    pub fn binding<F>(get: impl FnOnce(&UnrealBindings) -> Option<F>, name: &'static str) -> Result<F, BindingError>{{
+        try_bindings().and_then(get).ok_or(BindingError(name))
+    }}
+    ///table of other size is from other generated Binder.cpp and is refused
+    #[no_mangle]
+    unsafe extern "C" fn register_unreal_bindings(bindings: *const UnrealBindings, size: u32) -> bool{{
+        if bindings.is_null() || size as usize != std::mem::size_of::<UnrealBindings>(){{
+            println!("unreal bindings of size {{size}} are refused, regenerate binders");
+            return false;
+        }}
+        BINDINGS.set(unsafe{{ std::ptr::read(bindings) }}).is_ok()
+    }}
+}}
+///native functions never registered by register_all of Binder.cpp, empty if the table is registered
+///(Binder.cpp fills every entry of the table, so it is either complete or not registered)
+pub fn verify_all_handlers() -> Vec<&'static str>{{
+    if try_bindings().is_some(){{
+        return vec![];
+    }}
+    BINDING_NAMES.to_vec()
+}}"#)
+}
+///cpp side of native function table, returns table declaration and registration in register_all
+fn gen_cpp_bindings(bindings: &[(String, String)]) -> (String, String){
+    let fields = bindings.iter().map(|(name, _)| format!("\r\n    decltype(&::{name}) {name};")).collect::<String>();
+    let values = bindings.iter().map(|(name, _)| format!("\r\n        &{name},")).collect::<String>();
+    (
+        format!(r#"
+struct UnrealBindings {{{fields}
+}};
+using register_unreal_bindings_handler = bool(*)(const UnrealBindings* bindings, uint32 size);"#),
+        format!(r#"
+    static const UnrealBindings bindings = {{{values}
+    }};
+    auto const api_register_unreal_bindings = (register_unreal_bindings_handler)plugin->GetDllExport(TEXT("register_unreal_bindings\0"));
+    if(!api_register_unreal_bindings || !api_register_unreal_bindings(&bindings, sizeof(UnrealBindings))){{
+        UE_LOG(LogTemp, Error, TEXT("fail to register unreal bindings"));
+    }}"#)
+    )
+}
 fn export_enums(generator: &mut CodeGenerator, uenum: &CppEnum) -> anyhow::Result<()>{
     let repc = if uenum.namespace_enum{"#[repr(C)]"}else{"#[repr(u8)]"};
     let mut enum_content = vec![
@@ -286,21 +442,254 @@ fn export_enums(generator: &mut CodeGenerator, uenum: &CppEnum) -> anyhow::Resul
     generator.rs_enums.append(&mut enum_content);
     Ok(())
 }
+///rust types and To{rs_type}/To{cpp_type} converters of TypeWrapper with field mapping
+fn gen_wrappers(engine: &Engine, generator: &mut CodeGenerator, settings: &CustomSettings) -> anyhow::Result<()>{
+    let mapped_wrappers = settings.TypeWrapper.iter().filter_map(|wrapper| match wrapper {
+        TypeWrapper::Mapped(mapped) => Some(mapped),
+        TypeWrapper::Alias(_) => None,
+    });
+    for wrapper in mapped_wrappers {
+        let (cpp_type, rs_type) = (&wrapper.cpp_type, &wrapper.rs_type);
+        let Some(class) = engine.classes.iter().find(|class| &class.name == cpp_type) else{
+            anyhow::bail!("fail to generate wrapper {rs_type}, {cpp_type} is not parsed");
+        };
+        let members = members(engine, &class.properties);
+        if let Some(field) = wrapper.fields.iter().find(|field| members.iter().all(|member| member.name != field.cpp_field)){
+            anyhow::bail!("fail to generate wrapper {rs_type}, {cpp_type} has no public field {}", field.cpp_field);
+        }
+        let fields = wrapper.fields.iter().map(|field| format!("\tpub {}: {}", field.name, field.rs_type)).collect::<Vec<_>>().join(",\r\n");
+        generator.layouts.push(format!("{rs_type} as {cpp_type}{{{fields}}}"));
+        let derives = wrapper.derives.iter().map(|derive| format!(", {derive}")).collect::<String>();
+        generator.rs_source.push(format!(r#"#[repr(C)]
+#[derive(Default, Clone, Copy, Debug, PartialEq{derives})]
+pub struct {rs_type}{{
+{fields}
+}}"#));
+        //c++ mirror of the rust type, nested wrappers should be listed before
+        let cpp_fields = wrapper.fields.iter().map(|field| {
+            let c_type = crate::RUST_TO_C_TYPES.get(&field.rs_type).map_or(field.rs_type.as_str(), |(c_type, _)| c_type.as_str());
+            format!("\r\n    {c_type} {};", field.name)
+        }).collect::<String>();
+        generator.header.push(format!("struct {rs_type} {{{cpp_fields}\r\n}};\r\n{rs_type} To{rs_type}(const {cpp_type}& value);\r\n{cpp_type} To{cpp_type}(const {rs_type}& value);"));
+        //fields of wrapper types are converted by their converters, numbers are casted
+        let nested = |field: &WrapperField| settings.TypeWrapper.iter().find(|wrapper| wrapper.rs_type() == field.rs_type);
+        let to_rs = wrapper.fields.iter().map(|field| match nested(field) {
+            Some(_) => format!("To{}(value.{})", field.rs_type, field.cpp_field),
+            None => format!("static_cast<decltype({rs_type}::{})>(value.{})", field.name, field.cpp_field),
+        }).collect::<Vec<_>>().join(", ");
+        let to_cpp = wrapper.fields.iter().map(|field| match nested(field) {
+            Some(nested) => format!("\r\n    result.{} = To{}(value.{});", field.cpp_field, nested.cpp_type(), field.name),
+            None => format!("\r\n    result.{0} = static_cast<decltype(result.{0})>(value.{1});", field.cpp_field, field.name),
+        }).collect::<String>();
+        generator.converters.push(format!(r#"{rs_type} To{rs_type}(const {cpp_type}& value){{
+    return {rs_type}{{ {to_rs} }};
+}}
+{cpp_type} To{cpp_type}(const {rs_type}& value){{
+    {cpp_type} result;{to_cpp}
+    return result;
+}}"#));
+    }
+    Ok(())
+}
+///rust type of wrapper.rs or mapped TypeWrapper
+enum RsDefinition{
+    ///#[repr(C)] struct or union with field types
+    Record{ is_union: bool, fields: Vec<String> },
+    Alias(String),
+    ///#[repr(u32)] enum etc.
+    Scalar(String),
+}
+///#[repr] types and type aliases of wrapper.rs, key is the type name
+fn rs_definitions(source: &str) -> syn::Result<BTreeMap<String, RsDefinition>>{
+    let mut definitions = BTreeMap::new();
+    for item in syn::parse_file(source)?.items {
+        let (name, definition) = match item {
+            syn::Item::Type(alias) if alias.generics.params.is_empty() => {
+                let Some(alias_type) = rs_type_str(&alias.ty) else{
+                    continue;
+                };
+                (alias.ident, RsDefinition::Alias(alias_type))
+            },
+            syn::Item::Enum(scalar) => match repr(&scalar.attrs) {
+                Some(repr) if repr != "C" => (scalar.ident, RsDefinition::Scalar(repr)),
+                _ => continue,
+            },
+            syn::Item::Struct(record) if record.generics.params.is_empty() && repr(&record.attrs).as_deref() == Some("C") => {
+                let fields = record.fields.iter().map(|field| rs_type_str(&field.ty)).collect::<Option<_>>();
+                let Some(fields) = fields else{
+                    continue;
+                };
+                (record.ident, RsDefinition::Record { is_union: false, fields })
+            },
+            syn::Item::Union(record) if record.generics.params.is_empty() && repr(&record.attrs).as_deref() == Some("C") => {
+                let fields = record.fields.named.iter().map(|field| rs_type_str(&field.ty)).collect::<Option<_>>();
+                let Some(fields) = fields else{
+                    continue;
+                };
+                (record.ident, RsDefinition::Record { is_union: true, fields })
+            },
+            _ => continue,
+        };
+        definitions.insert(name.to_string(), definition);
+    }
+    Ok(definitions)
+}
+///`C` of `#[repr(C)]`, None if there is no single repr
+fn repr(attrs: &[syn::Attribute]) -> Option<String>{
+    attrs.iter()
+    .find(|attr| attr.path().is_ident("repr"))
+    .and_then(|attr| attr.parse_args::<syn::Ident>().ok())
+    .map(|repr| repr.to_string())
+}
+///type in the form rs_layout knows(`Real`, `*mut c_void`, `[f32; 4]`), None for generic or other types
+fn rs_type_str(rs_type: &syn::Type) -> Option<String>{
+    match rs_type {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let segments = path.path.segments.iter().map(|segment| {
+                segment.arguments.is_none().then(|| segment.ident.to_string())
+            }).collect::<Option<Vec<_>>>()?;
+            Some(segments.join("::"))
+        },
+        syn::Type::Ptr(_) => Some("*mut c_void".to_string()),
+        syn::Type::Array(array) => {
+            let syn::Expr::Lit(syn::ExprLit{ lit: syn::Lit::Int(len), .. }) = &array.len else{
+                return None;
+            };
+            Some(format!("[{}; {}]", rs_type_str(&array.elem)?, len.base10_parse::<usize>().ok()?))
+        },
+        _ => None,
+    }
+}
+///size and align of rust primitive type, pointers take the size of clang target
+fn rs_primitive_layout(rs_type: &str, pointer_size: usize) -> Option<(usize, usize)>{
+    let pointer = (pointer_size > 0).then_some((pointer_size, pointer_size));
+    if rs_type.starts_with("*mut ") || rs_type.starts_with("*const "){
+        return pointer;
+    }
+    match rs_type {
+        "i8" | "u8" | "bool" => Some((1, 1)),
+        "i16" | "u16" => Some((2, 2)),
+        "i32" | "u32" | "f32" | "char" => Some((4, 4)),
+        "i64" | "u64" | "f64" => Some((8, 8)),
+        "usize" | "isize" => pointer,
+        _ => None,
+    }
+}
+///size and align of rust type made of primitives and `definitions`
+fn rs_layout(rs_type: &str, definitions: &BTreeMap<String, RsDefinition>, pointer_size: usize, depth: usize) -> Option<(usize, usize)>{
+    if depth > 16{
+        return None;
+    }
+    if let Some(layout) = rs_primitive_layout(rs_type, pointer_size){
+        return Some(layout);
+    }
+    if let Some((element, len)) = rs_type.strip_prefix('[').and_then(|array| array.strip_suffix(']')?.split_once(';')){
+        let (size, align) = rs_layout(element.trim(), definitions, pointer_size, depth + 1)?;
+        return Some((size * len.trim().parse::<usize>().ok()?, align));
+    }
+    match definitions.get(rs_type)? {
+        RsDefinition::Alias(alias) | RsDefinition::Scalar(alias) => rs_layout(alias, definitions, pointer_size, depth + 1),
+        RsDefinition::Record { is_union, fields } => {
+            let (mut size, mut align) = (0usize, 1);
+            for field in fields {
+                let (field_size, field_align) = rs_layout(field, definitions, pointer_size, depth + 1)?;
+                let offset = if *is_union {0} else {size.div_ceil(field_align) * field_align};
+                size = size.max(offset + field_size);
+                align = align.max(field_align);
+            }
+            Some((size.div_ceil(align) * align, align))
+        },
+    }
+}
+///size and align of TypeWrapper rust types from wrapper.rs and the field mappings, key is the rust type
+fn wrapper_layouts(wrapper_source: &str, pointer_size: usize, settings: &CustomSettings) -> BTreeMap<String, (usize, usize)>{
+    let mut definitions = rs_definitions(wrapper_source).unwrap_or_else(|e| {
+        println!("fail to parse wrapper.rs: {e}, layouts of its types are unknown");
+        BTreeMap::new()
+    });
+    //precision of math wrappers
+    definitions.insert("Real".to_string(), RsDefinition::Alias(settings.real_type().to_string()));
+    for wrapper in &settings.TypeWrapper {
+        if let TypeWrapper::Mapped(mapped) = wrapper{
+            let fields = mapped.fields.iter().map(|field| field.rs_type.clone()).collect();
+            definitions.insert(mapped.rs_type.clone(), RsDefinition::Record { is_union: false, fields });
+        }
+    }
+    let mut layouts = BTreeMap::new();
+    for wrapper in &settings.TypeWrapper {
+        match rs_layout(wrapper.rs_type(), &definitions, pointer_size, 0) {
+            Some(layout) => {
+                layouts.insert(wrapper.rs_type().to_string(), layout);
+            },
+            None => println!("layout of wrapper {} is unknown, structs with {} fields are not checked", wrapper.rs_type(), wrapper.cpp_type()),
+        }
+    }
+    layouts
+}
+///c++ wrappers should have the layout of their rust types
+fn gen_wrapper_layout_checks(engine: &Engine, generator: &mut CodeGenerator, settings: &CustomSettings){
+    for wrapper in &settings.TypeWrapper {
+        if let Some((size, align)) = engine.wrapper_layouts.get(wrapper.rs_type()){
+            let rs_type = wrapper.rs_type();
+            generator.source.push(format!("    static_assert(sizeof({rs_type}) == {size} && alignof({rs_type}) == {align}, \"layout of {rs_type} differs from wrapper.rs\");"));
+        }
+    }
+}
+///structs with dumped layout are transparent only if their rust layout is known
+fn report_unknown_layouts(engine: &Engine, settings: &CustomSettings){
+    for export in &settings.ExportClasses {
+        let Some(class) = engine.classes.iter().find(|class| class.name == export.class_name) else{
+            continue;
+        };
+        if class.size > 0 && computed_layout(engine, class, settings).is_none(){
+            println!("rust layout of {} is unknown, it is transparent only if all fields are public and exported", class.name);
+        }
+    }
+}
+///`using Real` of math wrappers before the includes of header.h, RustApi.h defines the math wrappers with it
+fn insert_cpp_real(header: &str, settings: &CustomSettings) -> String{
+    let real = format!("//precision of math wrappers, same as Real of wrapper.rs\r\nusing Real = {};", settings.cpp_real_type());
+    let mut lines = header.lines().collect::<Vec<_>>();
+    let position = lines.iter().position(|line| line.starts_with("#pragma")).map_or(0, |i| i + 1);
+    lines.insert(position, &real);
+    lines.join("\r\n")
+}
 ///insert wrapped types into rust code
-fn insert_rs_wrappers(generator: &mut CodeGenerator) -> anyhow::Result<()>{
-    if let Ok(wrapper) = std::fs::read_to_string("Binders/wrapper.rs"){
+fn insert_rs_wrappers(generator: &mut CodeGenerator, settings: &CustomSettings) -> anyhow::Result<()>{
+    if let Ok(mut wrapper) = std::fs::read_to_string(Path::new("Binders").join("wrapper.rs")){
+        //precision of math wrappers
+        if settings.LargeWorldCoordinates{
+            let reals = [
+                ("pub type Real = f32;", "pub type Real = f64;"),
+                ("pub type RealVec3 = glam::Vec3;", "pub type RealVec3 = glam::DVec3;"),
+                ("pub type RealQuat = glam::Quat;", "pub type RealQuat = glam::DQuat;"),
+            ];
+            for (single, double) in reals {
+                if !wrapper.contains(single){
+                    println!("`{single}` is not found in wrapper.rs, math wrappers may not be f64");
+                }
+                wrapper = wrapper.replace(single, double);
+            }
+        }
         generator.rs_source.insert(generator.default_rs_header, wrapper);
     }
     Ok(())
 }
-///insert wrapped types into binder code
+///insert wrapped types and generated converters into binder code, converters follow binder.cpp whose converters they may call
 fn insert_cpp_wrappers(generator: &mut CodeGenerator) -> anyhow::Result<()>{
-    if let Ok(wrapper) = std::fs::read_to_string("Binders/binder.cpp"){
+    if !generator.converters.is_empty(){
+        generator.source.insert(generator.default_source_header, generator.converters.join("\r\n"));
+    }
+    if let Ok(wrapper) = std::fs::read_to_string(Path::new("Binders").join("binder.cpp")){
         generator.source.insert(generator.default_source_header, wrapper);
     }
     Ok(())
 }
 fn is_opaque(type_str: &str, engine: &Engine, settings: &CustomSettings) -> bool{
+    //smart pointer is opaque as its pointee
+    if let Some((_, pointee)) = SmartPtr::parse(type_str){
+        return is_opaque(&pointee, engine, settings);
+    }
     if is_wrapper_type(type_str, settings){
         return false;
     }
@@ -316,10 +705,18 @@ fn is_opaque(type_str: &str, engine: &Engine, settings: &CustomSettings) -> bool
     if let Some(class) = engine.classes.iter().find(|tp| {
         tp.name.as_str() == type_str        
     }){
-        !(class.none_public_properties.is_empty() && 
-        class.properties.iter().find(|p| {
-            p.bit_value || !should_export_property(engine, p)
-        }).is_none())
+        let exported = class.properties.iter().find(|p| {
+            p.bit_value || !should_export_property(engine, p) || (unnamed_record(engine, p).is_some() && is_opaque(&p.type_str, engine, settings))
+        }).is_none();
+        //with clang record layout, rust mirror of the fields should have the same layout,
+        //and static fields are the only non public fields(which take no space)
+        //structs of unknown rust layout are reported by report_unknown_layouts
+        if let Some(layout) = (class.size > 0).then(|| computed_layout(engine, class, settings)).flatten(){
+            return !(exported && class.none_public_properties.iter().all(|p| p.is_static) &&
+                layout.size == class.size && layout.align == class.align &&
+                layout.offsets.iter().zip(&class.properties).all(|((_, offset), p)| p.offset == Some(*offset)));
+        }
+        !(class.none_public_properties.is_empty() && exported)
     }
     else{
         true
@@ -336,6 +733,7 @@ fn gen_class(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator
     //2.所有成员变量不是位变量
     //3.所有成员变量不是泛型
     //4.所有成员变量类型均在导出列表中(或者基本数据类型(非字符串))
+    //dump了clang record layout时,1改为非public成员变量均为static,且成员变量布局与clang一致
     let is_opaque = is_opaque(&class.name, engine, settings);
     EXPORTED.lock().unwrap().push(TypeImpl{name: class.name.clone(), alis: class.path.clone(), is_opaque});
     //header
@@ -352,17 +750,28 @@ fn gen_class(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator
         gen_none_opaque(engine, class, generator, settings)?
     }
     generator.rs_source.push("}".to_string());
+    gen_overrides(engine, class, is_opaque, generator, settings);
     Ok(())
 }
 ///生成不透明对象的绑定信息
 fn gen_opaque(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings) -> anyhow::Result<()>{
+    gen_opaque_struct(engine, class, generator, settings);
+    parse_properties(engine, class, generator, true, settings)?;
+    parse_functions(engine, class, generator, true, settings)?;
+    Ok(())
+}
+///opaque rust struct and its trait impls, ends with open `impl` block
+fn gen_opaque_struct(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings){
     let ts = generator.insert_type(&class.name, engine, settings);
     //type imply
     // generator.header.push(format!("\tusing {} = void;", object_name));
     //rust type impl
     // generator.rs_source.push(format!("pub type {} = c_void;", object_name));
     let name = class.name.as_str();
-    generator.rs_source.push(format!(r#"pub struct {name}{{
+    let type_impls = gen_type_impls(engine, class, generator, settings).join("\r\n");
+    generator.rs_source.push(format!(r#"#[repr(transparent)]
+#[derive(PartialEq, Eq, Hash)]
+pub struct {name}{{
     inner: *mut {}
 }}
 impl IPtr for {name}{{
@@ -377,38 +786,402 @@ impl IPtr for {name}{{
         )        
     }}
 }}
+{}
 impl {name}{{
-    "#, ts.alis, ts.alis));
-    parse_properties(engine, class, generator, true, settings)?;
-    parse_functions(engine, class, generator, true, settings)?;
-    Ok(())
+    "#, ts.alis, ts.alis, type_impls));
+}
+///trait impls of opaque class
+fn gen_type_impls(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings) -> Vec<String>{
+    let mut impls = gen_upcasts(engine, class, settings);
+    if class.name == "UObject" || class.inherit_chain.iter().any(|parent| parent == "UObject"){
+        impls.push(gen_static_class(class, generator));
+    }
+    impls
+}
+///UClass getter for checked cast
+fn gen_static_class(class: &UnrealClass, generator: &mut CodeGenerator) -> String{
+    let name = class.name.as_str();
+    let cpp_api_name = format!("uapi_{name}_StaticClass");
+    generator.source.push(format!(r#"
+    void* {cpp_api_name}() {{ return {name}::StaticClass(); }};"#));
+    generator.push_binding(&cpp_api_name, "unsafe extern \"C\" fn() -> *mut c_void".to_string());
+    format!(r#"impl IUObject for {name}{{
+    #[inline]
+    fn static_class() -> *mut c_void {{
+        try_bindings().and_then(|bindings| bindings.{cpp_api_name}).map(|handler| unsafe{{ handler() }}).unwrap_or(std::ptr::null_mut())
+    }}
+}}"#)
+}
+///upcast into exported opaque base classes, and deref to the nearest one,
+///only primary bases are in inherit chain so the object address is not changed
+fn gen_upcasts(engine: &Engine, class: &UnrealClass, settings: &CustomSettings) -> Vec<String>{
+    let name = class.name.as_str();
+    let parents = class.inherit_chain.iter()
+    .filter(|parent| {
+        settings.ExportClasses.iter().any(|c| &c.class_name == *parent) &&
+        engine.classes.iter().any(|c| &c.name == *parent) &&
+        is_opaque(parent, engine, settings)
+    })
+    .collect::<Vec<_>>();
+    let mut upcasts = vec![];
+    for parent in &parents {
+        upcasts.push(format!(r#"impl From<{name}> for {parent}{{
+    #[inline]
+    fn from(value: {name}) -> Self {{ Self{{inner: value.inner}} }}
+}}
+impl AsRef<{parent}> for {name}{{
+    #[inline]
+    fn as_ref(&self) -> &{parent} {{ unsafe{{ &*(self as *const Self as *const {parent}) }} }}
+}}"#));
+    }
+    if let Some(parent) = parents.first(){
+        upcasts.push(format!(r#"impl Deref for {name}{{
+    type Target = {parent};
+    #[inline]
+    fn deref(&self) -> &Self::Target {{ unsafe{{ &*(self as *const Self as *const {parent}) }} }}
+}}
+impl DerefMut for {name}{{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {{ unsafe{{ &mut *(self as *mut Self as *mut {parent}) }} }}
+}}"#));
+    }
+    upcasts
+}
+///`ARustActor` of `AActor`
+fn rust_subclass_name(name: &str) -> String{
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(prefix @ ('U' | 'A')), Some(next)) if next.is_ascii_uppercase() => format!("{prefix}Rust{}", &name[1..]),
+        _ => format!("URust{name}"),
+    }
+}
+///virtual function whose parameters and result can be copied across ffi
+struct OverrideApi<'a>{
+    api: &'a CppApi,
+    ///parameter name, cpp declaration and ffi type
+    params: Vec<(String, String, ElementType)>,
+    ret: Option<ElementType>,
+}
+impl<'a> OverrideApi<'a>{
+    ///None if any parameter is output or generic, or result is reference
+    fn parse(api: &'a CppApi, engine: &Engine, settings: &CustomSettings) -> Option<Self>{
+        let mut params = vec![];
+        for param in &api.parameters {
+            if param.move_param || param.is_generic || (param.ref_param && !param.const_param){
+                return None;
+            }
+            let type_str = if param.ptr_param{ format!("{}*", param.type_str) } else { param.type_str.clone() };
+            let element = ElementType::parse(&type_str, engine, settings)?;
+            let decl = format!("{}{}{} {}", 
+                if param.const_param{ "const " } else { "" }, type_str, if param.ref_param{ "&" } else { "" }, param.name);
+            params.push((param.name.clone(), decl, element));
+        }
+        let ret = if is_void(&api.rc_type) && !api.ptr_ret{
+            None
+        }
+        else if api.ref_ret || api.is_generic{
+            return None;
+        }
+        else{
+            let type_str = if api.ptr_ret{ format!("{}*", api.rc_type) } else { api.rc_type.clone() };
+            Some(ElementType::parse(&type_str, engine, settings)?)
+        };
+        Some(Self{ api, params, ret })
+    }
+    fn cpp_ret_type(&self) -> String{
+        match &self.ret {
+            Some(ret) => format!("{}{}", if self.api.const_ret{ "const " } else { "" }, ret.c_type),
+            None => "void".to_string(),
+        }
+    }
+    fn cpp_ffi_ret_type(&self) -> String{
+        self.ret.as_ref().map(|ret| ret.ffi_type.clone()).unwrap_or("void".to_string())
+    }
+}
+///uclass `ARustActor : public AActor` forwarding selected virtual functions to rust handlers,
+///void functions call super first and others return rust result if handler is set, super result otherwise
+fn gen_overrides(engine: &Engine, class: &UnrealClass, is_opaque: bool, generator: &mut CodeGenerator, settings: &CustomSettings){
+    let class_to_export = settings.ExportClasses.iter().find(|c| c.class_name == class.name).unwrap();
+    if class_to_export.overrides.is_empty(){
+        return;
+    }
+    if !is_opaque || (class.name != "UObject" && !class.inherit_chain.iter().any(|parent| parent == "UObject")){
+        println!("overrides of {} are skipped, only opaque UObject class can be subclassed", class.name);
+        return;
+    }
+    let mut apis = vec![];
+    for function in &class_to_export.overrides {
+        //nearest declaration in inherit chain
+        let api = std::iter::once(class)
+        .chain(class.inherit_chain.iter().filter_map(|parent| engine.classes.iter().find(|c| &c.name == parent)))
+        .flat_map(|c| c.public_apis.iter().chain(c.virtual_apis.iter()))
+        .find(|api| &api.name == function && (api.is_virtual || api.is_override) && !api.is_static);
+        let Some(api) = api else{
+            println!("virtual function {}.{} not found", class.name, function);
+            continue;
+        };
+        match OverrideApi::parse(api, engine, settings) {
+            Some(api) => apis.push(api),
+            None => println!("virtual function {}.{} has unsupported parameters", class.name, function),
+        }
+    }
+    if apis.is_empty(){
+        return;
+    }
+    let parent = class.name.as_str();
+    let name = rust_subclass_name(parent);
+    let subclass = UnrealClass{
+        name: name.clone(),
+        inherit: parent.to_string(),
+        inherit_chain: std::iter::once(parent.to_string()).chain(class.inherit_chain.iter().cloned()).collect(),
+        opaque: true,
+        ..Default::default()
+    };
+    //uclass declaration
+    if !class.path.is_empty(){
+        generator.override_includes.push(format!("#include \"{}\"", class.path));
+    }
+    let include = "#include \"RustOverrides.h\"".to_string();
+    if !generator.include.contains(&include){
+        generator.include.push(include);
+    }
+    let declarations = apis.iter().map(|o| {
+        let params = o.params.iter().map(|(_, decl, _)| decl.as_str()).collect::<Vec<_>>().join(", ");
+        format!("\r\n    virtual {} {}({params}){} override;", o.cpp_ret_type(), o.api.name, if o.api.is_const{ " const" } else { "" })
+    }).collect::<String>();
+    generator.override_classes.push(format!(r#"UCLASS()
+class {name} : public {parent} {{
+    GENERATED_BODY()
+public:{declarations}
+}};"#));
+    gen_opaque_struct(engine, &subclass, generator, settings);
+    for o in &apis {
+        let function = o.api.name.as_str();
+        let handler_type = format!("{name}_{function}_override_handler");
+        let handler = format!("{name}_{function}_override");
+        let cpp_ffi_ret = o.cpp_ffi_ret_type();
+        //result is written into out_result, false if rust has no handler
+        let cpp_ffi_params = o.params.iter().map(|(param, _, element)| format!(", {} {param}", element.ffi_type)).collect::<String>() +
+            &o.ret.as_ref().map(|_| format!(", {cpp_ffi_ret}* out_result")).unwrap_or_default();
+        let params = o.params.iter().map(|(_, decl, _)| decl.as_str()).collect::<Vec<_>>().join(", ");
+        let args = o.params.iter().map(|(param, _, _)| param.as_str()).collect::<Vec<_>>().join(", ");
+        let ffi_args = o.params.iter().map(|(param, _, element)| format!(", {}", element.cpp_to_ffi(param))).collect::<String>();
+        let body = match &o.ret {
+            Some(ret) => format!(r#"
+        {cpp_ffi_ret} result{{}};
+        if ({handler} && {handler}((void*)this{ffi_args}, &result)) return {};
+        return Super::{function}({args});"#, ret.cpp_from_ffi("result")),
+            None => format!(r#"
+        Super::{function}({args});
+        if ({handler}) {handler}((void*)this{ffi_args});"#),
+        };
+        let setter = format!("uapi_{name}_{function}_override");
+        generator.source.push(format!(r#"
+    using {handler_type} = bool(*)(void* target{cpp_ffi_params});
+    {handler_type} {handler} = nullptr;
+    void {setter}({handler_type} handler) {{ {handler} = handler; }};
+    {} {name}::{function}({params}){} {{{body}
+    }};"#, o.cpp_ret_type(), if o.api.is_const{ " const" } else { "" }));
+        //rust registration of override setter
+        let rs_ffi_params = o.params.iter().map(|(param, _, element)| format!(", {param}: {}", element.rs_ffi_type())).collect::<String>() +
+            &o.ret.as_ref().map(|ret| format!(", out_result: *mut {}", ret.rs_ffi_type())).unwrap_or_default();
+        let rs_types = o.params.iter().map(|(_, _, element)| format!(", {}", element.rs_value_type())).collect::<String>();
+        let rs_ret = o.ret.as_ref().map(|ret| format!(" -> {}", ret.rs_value_type())).unwrap_or_default();
+        let handler_fn = format!("fn(&mut {name}{rs_types}){rs_ret}");
+        let override_type = format!("unsafe extern \"C\" fn(target: *mut c_void{rs_ffi_params}) -> bool");
+        let invoker = generator.push_binding(&setter, format!("unsafe extern \"C\" fn(handler: Option<{override_type}>)"));
+        //rust handler setter
+        let handler_args = o.params.iter().map(|(param, _, element)| format!(", {}", element.rs_from_ffi(param))).collect::<String>();
+        let (docs, dispatch) = match &o.ret {
+            Some(ret) => (
+                format!("forward {function} into rust instead of {parent}::{function}, None to restore"),
+                format!("let result = handler(&mut {name}{{inner: target}}{handler_args});\r\n                out_result.write({});", ret.rs_to_ffi("result"))
+            ),
+            None => (
+                format!("forward {function} into rust after {parent}::{function}, None to restore"),
+                format!("handler(&mut {name}{{inner: target}}{handler_args});")
+            ),
+        };
+        generator.rs_source.push(format!(r#"
+    ///{docs}
+    pub fn set_{function}_handler(handler: Option<{handler_fn}>){}{{
+        static HANDLER: std::sync::Mutex<Option<{handler_fn}>> = std::sync::Mutex::new(None);
+        unsafe extern "C" fn dispatch(target: *mut c_void{rs_ffi_params}) -> bool{{
+            //native side falls back to {parent}::{function} if handler is gone or panics
+            let Some(handler) = HANDLER.lock().ok().and_then(|handler| *handler) else{{
+                return false;
+            }};
+            //panic(e.g. of unregistered bindings) must not unwind into unreal
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(||{{
+                {dispatch}
+            }}))
+            .map_err(|_| error!("rust handler of {name}::{function} panicked"))
+            .is_ok()
+        }}
+        let set_override = {invoker};
+        //handler is ready before native side calls dispatch and kept until it stops
+        if handler.is_some(){{
+            *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = handler;
+        }}
+        unsafe{{ set_override(handler.map(|_| dispatch as {override_type})) }};
+        if handler.is_none(){{
+            *HANDLER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }}{}
+    }}"#, generator.rs_ret(""), if generator.checked_bindings{ "\r\n        Ok(())" } else { "" }));
+    }
+    generator.rs_source.push("}".to_string());
 }
 ///生成透明对象的绑定信息
 fn gen_none_opaque(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings) -> anyhow::Result<()>{
-    let object_name = format!("{}", class.name);
+    let object_name = class.name.to_string();
     //包装类型不定义类型
     if !is_wrapper_type(&class.name, settings){
+        gen_records(engine, class, generator, settings);
+        let fields = rs_fields(class, settings);
+        generator.layouts.push(format!("{object_name}{{{fields}}}"));
+        let layout_checks = gen_layout_checks(engine, class, generator, settings);
         //rust type impl
         generator.rs_source.push(format!(r#"#[repr(C)]
-pub struct {}{{
+pub {} {}{{
 {}
-}}
+}}{layout_checks}
 impl {}{{"#, 
+        if class.is_union {"union"} else {"struct"},
         object_name, 
-        class.properties.iter().map(|field|{
-            format!("\tpub {}: {}", field.name, field.r_type)
-        }).collect::<Vec<_>>().join(",\r\n"),
+        fields,
         object_name));
     }
     else{
+        generator.layouts.push(format!("{object_name} as {}", get_wrapper_type(&class.name, settings)));
         generator.rs_source.push(format!("impl {} {{", object_name));
     }
     // parse_properties(engine, class, generator, &mut rs_handlers)?;
     parse_functions(engine, class, generator, false, settings)?;
     Ok(())
 }
+///rust fields of transparent struct, union fields which are not primary are wrapped by ManuallyDrop
+fn rs_fields(class: &UnrealClass, settings: &CustomSettings) -> String{
+    class.properties.iter().map(|field|{
+        //wrapper fields are mirrored by the rust wrapper type
+        let r_type = if is_wrapper_type(&field.type_str, settings){ get_wrapper_type(&field.type_str, settings) } else { field.r_type.clone() };
+        let r_type = if field.array_dim > 0{
+            format!("[{r_type}; {}]", field.array_dim)
+        }
+        else{
+            r_type
+        };
+        if class.is_union && !is_rs_primary(&field.r_type, settings){
+            format!("\tpub {}: std::mem::ManuallyDrop<{r_type}>", field.name)
+        }
+        else{
+            format!("\tpub {}: {r_type}", field.name)
+        }
+    }).collect::<Vec<_>>().join(",\r\n")
+}
+///unions and structs without type name can not be named in c++, they are only defined in rust and checked by the owner
+fn gen_records(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings){
+    //anonymous members and named fields(`struct {...} Box;`) alike
+    for record in class.properties.iter().filter_map(|p| unnamed_record(engine, p)) {
+        gen_records(engine, record, generator, settings);
+        let fields = rs_fields(record, settings);
+        generator.layouts.push(format!("{}{{{fields}}}", record.name));
+        generator.rs_source.push(format!(r#"#[repr(C)]
+pub {} {}{{
+{fields}
+}}"#, if record.is_union {"union"} else {"struct"}, record.name));
+    }
+}
+///generated record of the field type, if the type has no name in c++
+fn unnamed_record<'a>(engine: &'a Engine, property: &CppProperty) -> Option<&'a UnrealClass>{
+    engine.classes.iter().find(|c| c.is_unnamed && c.name == property.type_str)
+}
+///fields with members of anonymous records flattened, they are accessed as fields of the owner in c++
+fn members<'a>(engine: &'a Engine, properties: &'a [CppProperty]) -> Vec<&'a CppProperty>{
+    properties.iter().flat_map(|property| {
+        match engine.classes.iter().find(|c| property.anonymous && c.name == property.type_str) {
+            Some(record) => members(engine, &record.properties),
+            None => vec![property],
+        }
+    }).collect()
+}
+///c++ member for offsetof, members of anonymous record are accessed through the owner
+fn cpp_member(engine: &Engine, property: &CppProperty) -> String{
+    if property.anonymous{
+        if let Some(first) = engine.classes.iter().find(|c| c.name == property.type_str).and_then(|record| record.properties.first()){
+            return cpp_member(engine, first);
+        }
+    }
+    property.name.clone()
+}
+///c layout of a transparent struct
+struct RecordLayout{
+    size: usize,
+    align: usize,
+    ///offset of each field
+    offsets: Vec<(String, usize)>,
+}
+///size and align of field type, None if it is unknown(enums, wrappers not in wrapper.rs and pointers of unknown target)
+fn field_layout(engine: &Engine, property: &CppProperty, settings: &CustomSettings) -> Option<(usize, usize)>{
+    if property.is_ptr{
+        return rs_primitive_layout("usize", engine.pointer_size);
+    }
+    if is_wrapper_type(&property.type_str, settings){
+        return engine.wrapper_layouts.get(&get_wrapper_type(&property.type_str, settings)).copied();
+    }
+    if let Some((rs_type, _)) = crate::get_c2r_types(&property.type_str){
+        return rs_primitive_layout(&rs_type, engine.pointer_size);
+    }
+    engine.classes.iter()
+    .find(|class| class.name == property.type_str && !is_opaque(&class.name, engine, settings))
+    .and_then(|class| struct_layout(engine, class, settings))
+    .map(|layout| (layout.size, layout.align))
+}
+///c layout of transparent struct, clang record layout if it is dumped
+fn struct_layout(engine: &Engine, class: &UnrealClass, settings: &CustomSettings) -> Option<RecordLayout>{
+    if class.size > 0{
+        let offsets = class.properties.iter().map(|p| Some((p.name.clone(), p.offset?))).collect::<Option<Vec<_>>>()?;
+        return Some(RecordLayout { size: class.size, align: class.align, offsets });
+    }
+    computed_layout(engine, class, settings)
+}
+///c layout of the rust mirror of struct fields, None if any field is unknown or a bit field
+fn computed_layout(engine: &Engine, class: &UnrealClass, settings: &CustomSettings) -> Option<RecordLayout>{
+    let (mut size, mut align) = (0usize, 1);
+    let mut offsets = vec![];
+    for property in &class.properties {
+        //bit fields share storage units
+        if property.bit_value{
+            return None;
+        }
+        let (field_size, field_align) = field_layout(engine, property, settings)?;
+        //members of union all start at 0
+        let offset = if class.is_union {0} else {size.div_ceil(field_align) * field_align};
+        offsets.push((property.name.clone(), offset));
+        size = size.max(offset + field_size * property.array_dim.max(1));
+        align = align.max(field_align);
+    }
+    Some(RecordLayout { size: size.div_ceil(align) * align, align, offsets })
+}
+///static_assert of cpp and const assert of rust, both sides should have the same layout
+fn gen_layout_checks(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, settings: &CustomSettings) -> String{
+    let name = class.name.as_str();
+    let Some(layout) = struct_layout(engine, class, settings) else{
+        println!("layout of {name} is not checked, size of some fields is unknown");
+        return String::new();
+    };
+    let (size, align) = (layout.size, layout.align);
+    let message = format!("\"layout of {name} is changed, regenerate binders\"");
+    generator.source.push(format!(r#"
+    static_assert(sizeof({name}) == {size} && alignof({name}) == {align}, {message});{}"#,
+    layout.offsets.iter().zip(&class.properties).map(|((_, offset), property)| {
+        format!("\r\n    static_assert(offsetof({name}, {}) == {offset}, {message});", cpp_member(engine, property))
+    }).collect::<String>()));
+    format!(r#"
+const _: () = assert!(std::mem::size_of::<{name}>() == {size} && std::mem::align_of::<{name}>() == {align});{}"#,
+    layout.offsets.iter().map(|(field, offset)| format!("\r\nconst _: () = assert!(std::mem::offset_of!({name}, {field}) == {offset});")).collect::<String>())
+}
 fn is_void(type_str: &str) -> bool{
-    type_str == "void" || type_str == ""
+    type_str == "void" || type_str.is_empty()
 }
 fn export_type(type_str: &str, settings: &CustomSettings) -> bool{
     is_primary(type_str, settings) || settings.ExportClasses.iter().find(|x| x.class_name.as_str() == type_str).is_some()
@@ -428,19 +1201,412 @@ fn black_field(field: &CppProperty, settings: &CustomSettings) -> bool{
     settings.BlackList.iter().find(|bl| *bl == &field.name).is_some()
 }
 fn get_wrapper_type(cpp_type: &str, settings: &CustomSettings) -> String{
-    if let Some(tp) = settings.TypeWrapper.iter().find(|x| x[0].as_str() == cpp_type){
-        tp[1].clone()
+    if let Some(tp) = settings.TypeWrapper.iter().find(|x| x.cpp_type() == cpp_type){
+        tp.rs_type().to_string()
     }
     else{
         cpp_type.to_string()
     }
 }
 fn is_wrapper_type(cpp_type: &str, settings: &CustomSettings) -> bool{
-    settings.TypeWrapper.iter().find(|x| x[0].as_str() == cpp_type).is_some()
+    settings.TypeWrapper.iter().find(|x| x.cpp_type() == cpp_type).is_some()
+}
+///wrapper.rs types deriving Eq and Hash, math wrappers(Vector3, Rotator...) only derive PartialEq
+const HASHABLE_WRAPPERS: [&str; 4] = ["UName", "Color", "IntPoint", "Entity"];
+///wrapper type can be key of HashMap/HashSet
+fn is_hashable_wrapper(cpp_type: &str, settings: &CustomSettings) -> bool{
+    match settings.TypeWrapper.iter().find(|x| x.cpp_type() == cpp_type) {
+        Some(TypeWrapper::Alias([_, rs_type])) => HASHABLE_WRAPPERS.contains(&rs_type.as_str()),
+        Some(TypeWrapper::Mapped(mapped)) => ["Eq", "Hash"].iter().all(|derive| mapped.derives.iter().any(|d| d == derive)),
+        None => false,
+    }
 }
 fn is_string_type(cpp_type: &str) -> bool{
     cpp_type == "FString" || cpp_type == "FText"
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementKind{
+    Primary,
+    Wrapper,
+    ///ptr of exported opaque class
+    Object,
+    ///exported enum
+    Enum,
+}
+///container element which can be copied across ffi
+#[derive(Debug, Clone)]
+struct ElementType{
+    ///unreal element type
+    c_type: String,
+    ///element type of ffi buffer
+    ffi_type: String,
+    ///element type of rust slice or collection
+    rs_type: String,
+    kind: ElementKind,
+}
+impl ElementType{
+    ///primary, wrapper or exported opaque ptr element
+    fn parse(type_str: &str, engine: &Engine, settings: &CustomSettings) -> Option<Self>{
+        //nested container
+        if type_str.contains('<'){
+            return None;
+        }
+        if let Some(object) = type_str.strip_suffix('*'){
+            let object = object.trim();
+            if settings.ExportClasses.iter().any(|c| c.class_name == object) && is_opaque(object, engine, settings){
+                return Some(Self{
+                    c_type: format!("{object}*"),
+                    ffi_type: "void*".to_string(),
+                    rs_type: object.to_string(),
+                    kind: ElementKind::Object,
+                });
+            }
+            return None;
+        }
+        if is_wrapper_type(type_str, settings){
+            let wrapper_type = get_wrapper_type(type_str, settings);
+            return Some(Self{
+                c_type: type_str.to_string(),
+                ffi_type: wrapper_type.clone(),
+                rs_type: wrapper_type,
+                kind: ElementKind::Wrapper,
+            });
+        }
+        if let Some(uenum) = settings.ExportEnums.iter().find(|e| e.as_str() == type_str || format!("{e}::Type") == type_str){
+            return Some(Self{
+                c_type: type_str.to_string(),
+                ffi_type: type_str.to_string(),
+                rs_type: uenum.clone(),
+                kind: ElementKind::Enum,
+            });
+        }
+        if is_void(type_str){
+            return None;
+        }
+        crate::get_c2r_types(type_str).map(|(rs_type, _)| Self{
+            c_type: type_str.to_string(),
+            ffi_type: type_str.to_string(),
+            rs_type,
+            kind: ElementKind::Primary,
+        })
+    }
+    ///can be key of HashMap/HashSet
+    fn hashable(&self, settings: &CustomSettings) -> bool{
+        match self.kind {
+            ElementKind::Primary => !matches!(self.rs_type.as_str(), "f32" | "f64"),
+            ElementKind::Wrapper => is_hashable_wrapper(&self.c_type, settings),
+            ElementKind::Enum => false,
+            ElementKind::Object => true,
+        }
+    }
+    ///cpp expression copying NativeArray/RefArray `name` into TArray
+    fn to_tarray(&self, name: &str) -> String{
+        let (c_type, ffi_type) = (&self.c_type, &self.ffi_type);
+        match self.kind {
+            ElementKind::Wrapper => format!("ToTArray<{c_type}, {ffi_type}>({name}.data, {name}.len, [](const {ffi_type}& v){{ return To{c_type}(v); }})"),
+            _ => format!("ToTArray<{c_type}>({name}.data, {name}.len)"),
+        }
+    }
+    ///cpp statement copying TArray `array` into rust vec of RefArray `out`
+    fn reset_rust_array(&self, array: &str, out: &str) -> String{
+        let (c_type, ffi_type) = (&self.c_type, &self.ffi_type);
+        match self.kind {
+            ElementKind::Primary | ElementKind::Enum => format!("ResetRustArray({array}, {out});"),
+            ElementKind::Wrapper => format!("ResetRustArray<{c_type}, {ffi_type}>({array}, {out}, [](const {c_type}& v){{ return To{ffi_type}(v); }});"),
+            ElementKind::Object => format!("ResetRustObjectArray({array}, {out});"),
+        }
+    }
+    ///rust expression copying element ref `value`
+    fn rs_copy(&self, value: &str) -> String{
+        match self.kind {
+            ElementKind::Object => format!("{}{{inner: {value}.inner}}", self.rs_type),
+            _ => format!("*{value}"),
+        }
+    }
+    ///cpp expression of native `value` as ffi value
+    fn cpp_to_ffi(&self, value: &str) -> String{
+        match self.kind {
+            ElementKind::Primary | ElementKind::Enum => value.to_string(),
+            ElementKind::Wrapper => format!("To{}({value})", self.ffi_type),
+            ElementKind::Object => format!("(void*){value}"),
+        }
+    }
+    ///cpp expression of ffi value `name` as native value
+    fn cpp_from_ffi(&self, name: &str) -> String{
+        match self.kind {
+            ElementKind::Primary | ElementKind::Enum => name.to_string(),
+            ElementKind::Wrapper => format!("To{}({name})", self.c_type),
+            ElementKind::Object => format!("({}){name}", self.c_type),
+        }
+    }
+    fn rs_ffi_type(&self) -> String{
+        match self.kind {
+            ElementKind::Object => "*mut c_void".to_string(),
+            _ => self.rs_type.clone(),
+        }
+    }
+    ///rust type of value passed to callback, object maybe nullptr
+    fn rs_value_type(&self) -> String{
+        match self.kind {
+            ElementKind::Object => format!("Option<{}>", self.rs_type),
+            _ => self.rs_type.clone(),
+        }
+    }
+    fn rs_param_type(&self) -> String{
+        match self.kind {
+            ElementKind::Object => format!("Option<&{}>", self.rs_type),
+            _ => self.rs_type.clone(),
+        }
+    }
+    ///rust expression of ffi value `name` as `rs_value_type`
+    fn rs_from_ffi(&self, name: &str) -> String{
+        match self.kind {
+            ElementKind::Object => format!("{}::from_ptr({name})", self.rs_type),
+            _ => name.to_string(),
+        }
+    }
+    ///rust expression of parameter `name` as ffi value
+    fn rs_to_ffi(&self, name: &str) -> String{
+        match self.kind {
+            ElementKind::Object => format!("{name}.map(|v| v.inner()).unwrap_or(std::ptr::null_mut())"),
+            _ => name.to_string(),
+        }
+    }
+}
+///dynamic multicast delegate whose parameters are copied across ffi as container elements
+struct FfiDelegate<'a>{
+    delegate: &'a CppDelegate,
+    ///parameter name and type
+    params: Vec<(String, ElementType)>,
+}
+impl<'a> FfiDelegate<'a>{
+    ///None if any parameter is output, generic or not primary, wrapper and exported opaque ptr
+    fn parse(delegate: &'a CppDelegate, engine: &Engine, settings: &CustomSettings) -> Option<Self>{
+        let mut params = vec![];
+        for param in &delegate.parameters {
+            if param.move_param || param.is_generic || (param.ref_param && !param.const_param){
+                return None;
+            }
+            let type_str = if param.ptr_param{ format!("{}*", param.type_str) } else { param.type_str.clone() };
+            params.push((param.name.clone(), ElementType::parse(&type_str, engine, settings)?));
+        }
+        Some(Self{ delegate, params })
+    }
+    ///single cast delegate type bound to trampoline
+    fn cpp_delegate_type(&self) -> String{
+        if self.delegate.sparse{
+            format!("{}_MCSignature::FDelegate", self.delegate.name)
+        }
+        else{
+            format!("{}::FDelegate", self.delegate.name)
+        }
+    }
+    fn cpp_ffi_params(&self) -> String{
+        self.params.iter().map(|(name, element)| format!(", {} {name}", element.ffi_type)).collect()
+    }
+    fn rs_ffi_params(&self) -> String{
+        self.params.iter().map(|(name, element)| format!(", {name}: {}", element.rs_ffi_type())).collect()
+    }
+    ///closure bound `FnMut(..)`
+    fn rs_closure_type(&self) -> String{
+        format!("FnMut({})", self.params.iter().map(|(_, element)| element.rs_value_type()).collect::<Vec<_>>().join(", "))
+    }
+}
+///smart pointer of exported opaque class, object ptr or weak handle across ffi
+#[derive(Debug, Clone)]
+struct SmartPtrType{
+    kind: SmartPtr,
+    pointee: String,
+}
+impl SmartPtrType{
+    fn parse(type_str: &str, engine: &Engine, settings: &CustomSettings) -> Option<Self>{
+        let (kind, pointee) = SmartPtr::parse(type_str)?;
+        //pointee should have rust wrapper of object ptr
+        if !settings.ExportClasses.iter().any(|c| c.class_name == pointee) || !is_opaque(&pointee, engine, settings){
+            return None;
+        }
+        Some(Self{ kind, pointee })
+    }
+    fn cpp_ffi_type(&self) -> &'static str{
+        match self.kind {
+            SmartPtr::WeakObjectPtr => "WeakObjectPtr",
+            _ => "void*",
+        }
+    }
+    ///cpp caster (begin, end) of smart pointer into ffi value
+    fn cpp_to_ffi(&self) -> (String, String){
+        match self.kind {
+            SmartPtr::WeakObjectPtr => ("ToWeakObjectPtr(".to_string(), ")".to_string()),
+            _ => ("(void*)(".to_string(), ").Get()".to_string()),
+        }
+    }
+    ///cpp expression building smart pointer from ffi value `name`
+    fn cpp_from_ffi(&self, name: &str) -> String{
+        let pointee = &self.pointee;
+        match self.kind {
+            SmartPtr::ObjectPtr => format!("TObjectPtr<{pointee}>(({pointee}*){name})"),
+            SmartPtr::SoftObjectPtr => format!("TSoftObjectPtr<{pointee}>(({pointee}*){name})"),
+            SmartPtr::WeakObjectPtr => format!("ToTWeakObjectPtr<{pointee}>({name})"),
+            SmartPtr::SubclassOf => format!("TSubclassOf<{pointee}>((UClass*){name})"),
+        }
+    }
+    fn rs_ffi_type(&self) -> String{
+        let pointee = &self.pointee;
+        match self.kind {
+            SmartPtr::WeakObjectPtr => format!("WeakObjectPtr<{pointee}>"),
+            SmartPtr::SubclassOf => format!("SubclassOf<{pointee}>"),
+            _ => "*mut c_void".to_string(),
+        }
+    }
+    ///rust type of result and field getter, object ptr maybe nullptr
+    fn rs_type(&self) -> String{
+        match self.kind {
+            SmartPtr::ObjectPtr | SmartPtr::SoftObjectPtr => format!("Option<{}>", self.pointee),
+            _ => self.rs_ffi_type(),
+        }
+    }
+    fn rs_param_type(&self) -> String{
+        match self.kind {
+            SmartPtr::ObjectPtr | SmartPtr::SoftObjectPtr => format!("Option<&{}>", self.pointee),
+            _ => self.rs_ffi_type(),
+        }
+    }
+    ///rust caster (begin, end) of ffi value into `rs_type`
+    fn rs_from_ffi(&self) -> (String, &'static str){
+        match self.kind {
+            SmartPtr::ObjectPtr | SmartPtr::SoftObjectPtr => (format!("{}::from_ptr(", self.pointee), ")"),
+            _ => (String::new(), ""),
+        }
+    }
+    ///rust expression of parameter `name` as ffi value
+    fn rs_to_ffi(&self, name: &str) -> String{
+        match self.kind {
+            SmartPtr::ObjectPtr | SmartPtr::SoftObjectPtr => format!("{name}.map(|v| v.inner()).unwrap_or(std::ptr::null_mut())"),
+            _ => name.to_string(),
+        }
+    }
+}
+///TArray/TSet/TMap copied across ffi by element arrays,
+///TArray as slice or Vec, TSet as HashSet and TMap as HashMap by key and value arrays
+#[derive(Debug, Clone)]
+enum FfiContainer{
+    Array(ElementType),
+    Set(ElementType),
+    Map(ElementType, ElementType),
+}
+impl FfiContainer{
+    fn parse(container: Option<&Container>, engine: &Engine, settings: &CustomSettings) -> Option<Self>{
+        let container = container?;
+        let mut elements = container.elements.iter().map(|element| ElementType::parse(element, engine, settings));
+        match container.kind {
+            ContainerKind::Array => Some(Self::Array(elements.next()??)),
+            ContainerKind::Set => Some(Self::Set(elements.next()??)).filter(|set| set.hashable(settings)),
+            ContainerKind::Map => Some(Self::Map(elements.next()??, elements.next()??)).filter(|map| map.hashable(settings)),
+        }
+    }
+    fn hashable(&self, settings: &CustomSettings) -> bool{
+        match self {
+            Self::Array(_) => true,
+            Self::Set(element) | Self::Map(element, _) => element.hashable(settings),
+        }
+    }
+    ///rust collection type
+    fn rs_type(&self) -> String{
+        match self {
+            Self::Array(element) => format!("Vec<{}>", element.rs_type),
+            Self::Set(element) => format!("HashSet<{}>", element.rs_type),
+            Self::Map(key, value) => format!("HashMap<{}, {}>", key.rs_type, value.rs_type),
+        }
+    }
+    ///rust parameter type, vec and collections can be modified by native side
+    fn rs_param_type(&self, mutable: bool) -> String{
+        match (self, mutable) {
+            (Self::Array(element), false) => format!("&[{}]", element.rs_type),
+            (_, false) => format!("&{}", self.rs_type()),
+            (_, true) => format!("&mut {}", self.rs_type()),
+        }
+    }
+    ///ffi array names of container `name`
+    fn ffi_names(&self, name: &str) -> Vec<String>{
+        match self {
+            Self::Array(_) => vec![name.to_string()],
+            Self::Set(_) => vec![format!("{name}_values")],
+            Self::Map(..) => vec![format!("{name}_keys"), format!("{name}_values")],
+        }
+    }
+    ///cpp parameters of container `name`
+    fn cpp_params(&self, name: &str, mutable: bool) -> String{
+        let ffi_type = if mutable{ "RefArray" } else { "NativeArray" };
+        self.ffi_names(name).iter().map(|name| format!("{ffi_type} {name}")).collect::<Vec<_>>().join(", ")
+    }
+    ///cpp expression building container from ffi arrays of `name`
+    fn to_native(&self, name: &str) -> String{
+        let names = self.ffi_names(name);
+        match self {
+            Self::Array(element) => element.to_tarray(&names[0]),
+            Self::Set(element) => format!("TSet<{}>({})", element.c_type, element.to_tarray(&names[0])),
+            Self::Map(key, value) => format!("ToTMap({}, {})", key.to_tarray(&names[0]), value.to_tarray(&names[1])),
+        }
+    }
+    ///cpp statement copying container `native` into rust vecs of ffi arrays of `name`
+    fn reset_rust(&self, native: &str, name: &str) -> String{
+        let names = self.ffi_names(name);
+        match self {
+            Self::Array(element) => element.reset_rust_array(native, &names[0]),
+            Self::Set(element) => element.reset_rust_array(&format!("{native}.Array()"), &names[0]),
+            Self::Map(key, value) => format!(
+                "{{ TArray<{}> keys; TArray<{}> values; SplitTMap({native}, keys, values); {} {} }}",
+                key.c_type, value.c_type, key.reset_rust_array("keys", &names[0]), value.reset_rust_array("values", &names[1])
+            ),
+        }
+    }
+    ///rust statements copying collection `name` into vecs of ffi arrays
+    fn rs_locals(&self, name: &str, mutable: bool) -> Vec<String>{
+        let names = self.ffi_names(name);
+        let mut_tag = if mutable{ "mut " } else { "" };
+        match self {
+            Self::Array(_) => vec![],
+            Self::Set(element) => vec![format!("let {mut_tag}{} = {name}.iter().map(|v| {}).collect::<Vec<_>>();", names[0], element.rs_copy("v"))],
+            Self::Map(key, value) => vec![format!(
+                "let ({mut_tag}{}, {mut_tag}{}): (Vec<_>, Vec<_>) = {name}.iter().map(|(k, v)| ({}, {})).unzip();",
+                names[0], names[1], key.rs_copy("k"), value.rs_copy("v")
+            )],
+        }
+    }
+    ///rust ffi arguments of collection `name` after `rs_locals`
+    fn rs_args(&self, name: &str, mutable: bool) -> Vec<String>{
+        match (self, mutable) {
+            (Self::Array(_), false) => vec![format!("NativeArray::new({name})")],
+            (Self::Array(_), true) => vec![format!("RefArray::new({name})")],
+            (_, false) => self.ffi_names(name).iter().map(|name| format!("NativeArray::new(&{name})")).collect(),
+            (_, true) => self.ffi_names(name).iter().map(|name| format!("RefArray::new(&mut {name})")).collect(),
+        }
+    }
+    ///rust expression collecting vecs of ffi arrays of `name`
+    fn rs_collect(&self, name: &str) -> String{
+        let names = self.ffi_names(name);
+        match self {
+            Self::Array(_) => names[0].clone(),
+            Self::Set(_) => format!("{}.into_iter().collect()", names[0]),
+            Self::Map(..) => format!("{}.into_iter().zip({}).collect()", names[0], names[1]),
+        }
+    }
+    ///rust statement writing native changes back to collection `name`
+    fn rs_write_back(&self, name: &str) -> Option<String>{
+        match self {
+            Self::Array(_) => None,
+            _ => Some(format!("*{name} = {};", self.rs_collect(name))),
+        }
+    }
+    ///rust statements of result vecs
+    fn rs_result_locals(&self, name: &str) -> Vec<String>{
+        self.ffi_names(name).iter().map(|name| format!("let mut {name} = Vec::new();")).collect()
+    }
+    ///rust ffi arguments of result vecs after `rs_result_locals`
+    fn rs_result_args(&self, name: &str) -> Vec<String>{
+        self.ffi_names(name).iter().map(|name| format!("RefArray::new(&mut {name})")).collect()
+    }
+}
 ///生成函数
 fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGenerator, opaque: bool, settings: &CustomSettings) -> anyhow::Result<()>{    
     let class_name = class.name.clone();
@@ -453,24 +1619,50 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
         if black_api(api, settings){
             continue;
         }
-        if api.name == "GetCharacterMovement"{
-            println!("pause");
-        }
         //not in white list
-        if class_to_export.functions.len() > 0 && class_to_export.functions.iter().find(|f| f.as_str() == api.name.as_str()).is_none(){
+        if !class_to_export.functions.is_empty() && class_to_export.functions.iter().find(|f| f.as_str() == api.name.as_str()).is_none(){
             continue;
         }
         //in ignore list
-        if class_to_export.functions.len() == 0 && class_to_export.ignore_functions.iter().find(|f| f.as_str() == api.name.as_str()).is_some(){
+        if class_to_export.functions.is_empty() && class_to_export.ignore_functions.iter().find(|f| f.as_str() == api.name.as_str()).is_some(){
+            continue;
+        }
+        //not blueprint visible
+        if class_to_export.functions.is_empty() && settings.blueprint_only(class_to_export) &&
+            !api.specifiers.has("BlueprintCallable") && !api.specifiers.has("BlueprintPure"){
             continue;
         }
-        if api.is_generic || api.rc_type.find("<").is_some(){
+        //container result is copied into rust collection
+        let container_ret = if api.ptr_ret{ None } else { FfiContainer::parse(api.ret_container.as_ref(), engine, settings) };
+        //smart pointer result is unwrapped into object ptr or weak handle
+        let smart_ret = if api.ptr_ret{ None } else { SmartPtrType::parse(&api.rc_type, engine, settings) };
+        //generic api, only containers and smart pointers are marshalled
+        if api.is_generic && (
+            (container_ret.is_none() && smart_ret.is_none() && api.rc_type.find("<").is_some()) ||
+            api.parameters.iter().any(|param| param.is_generic &&
+                FfiContainer::parse(param.container.as_ref(), engine, settings).is_none() &&
+                SmartPtrType::parse(&param.type_str, engine, settings).is_none())
+        ){
             continue;
         }
         let parameters = api.parameters.clone();
         //api with opaque(and not exported) none ptr parameter  will not export
         //TODO fix me by optimize class_parser template parser
         for param in &api.parameters {
+            //container is passed by slice or collection
+            if FfiContainer::parse(param.container.as_ref(), engine, settings).is_some(){
+                if param.ptr_param || param.move_param{
+                    continue 'api;
+                }
+                continue;
+            }
+            //smart pointer is passed by value
+            if SmartPtrType::parse(&param.type_str, engine, settings).is_some(){
+                if param.ptr_param || param.move_param || (param.ref_param && !param.const_param){
+                    continue 'api;
+                }
+                continue;
+            }
             if param.is_generic || 
             param.type_str.contains("<") ||
             param.type_str == "T" ||
@@ -496,10 +1688,6 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                     !wrapper_type{
                     continue 'api;
                 }
-                //ref mut wrapper type not supported yet(2 type converter)
-                if wrapper_type && param.ref_param{
-                    continue 'api;
-                }
             }
         }
         //generic do not export
@@ -512,18 +1700,16 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
         if is_string_ret && api.ref_ret{
             continue;
         }
-        let opaque_ret = !is_string_ret && is_opaque(&api.rc_type, engine, settings);
+        let opaque_ret = !is_string_ret && container_ret.is_none() && smart_ret.is_none() && is_opaque(&api.rc_type, engine, settings);
         //opaque is ptr or ref, else not export
         //for ref wrapped type, here we export a clone of rust version
         let wrapped_ret = is_wrapper_type(&api.rc_type, settings);
-        if !is_string_ret{
-            if opaque_ret && !api.ptr_ret && !wrapped_ret{
-                continue;
-            }
+        if !is_string_ret && !wrapped_ret && container_ret.is_none() && smart_ret.is_none() && (
+            (opaque_ret && !api.ptr_ret) ||
             //opaque but didn't export
-            else if !opaque_ret && !export_type(&api.rc_type, settings) && !wrapped_ret{
-                continue;
-            }
+            (!opaque_ret && !export_type(&api.rc_type, settings))
+        ){
+            continue;
         }
         // else{
         //     println!("string ret of class {} api {}", api.class_name, api.name);
@@ -540,7 +1726,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
         let mut lifetime_ret = false;
         //make ref result to ptr between ffi api
         let mut ref_to_ptr = false;
-        let rs_ret_type = generator.insert_rs_type(&api.r_type, engine, settings);
+        let rs_ret_type = if container_ret.is_some() || smart_ret.is_some(){ TypeImpl::default() } else { generator.insert_rs_type(&api.r_type, engine, settings) };
         let wrapper_class = is_wrapper_type(&api.class_name, settings);
         //api result type
         let (cpp_ret, rs_ret_liftime, rs_ret_origin) = match opaque_ret {
@@ -564,8 +1750,15 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 }
             },
             false => {
-                if api.rc_type == "void" || api.rc_type.is_empty(){
-                    ("void".to_string(), format!(""), format!(""))
+                if let Some(container) = &container_ret{
+                    //result is written into RefArray
+                    ("void".to_string(), format!(" -> {}", container.rs_type()), String::new())
+                }
+                else if let Some(smart) = &smart_ret{
+                    (smart.cpp_ffi_type().to_string(), format!(" -> {}", smart.rs_type()), format!(" -> {}", smart.rs_ffi_type()))
+                }
+                else if api.rc_type == "void" || api.rc_type.is_empty(){
+                    ("void".to_string(), String::new(), String::new())
                 }
                 else{
                     if api.ptr_ret{
@@ -586,7 +1779,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                     }
                     else{
                         if is_string_ret{
-                            ("const char*".to_string(), format!(" -> String"), format!(" -> *const std::os::raw::c_char"))
+                            ("const char*".to_string(), " -> String".to_string(), " -> *const std::os::raw::c_char".to_string())
                         }
                         else{
                             let rs_type = if is_wrapper_type(&api.rc_type, settings){ get_wrapper_type(&api.rc_type, settings)}else{rs_ret_type.name};
@@ -600,6 +1793,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
         let mut rs_ffi_parameters = vec![];
         let mut rs_fn_parameters = vec![];
         let mut rs_string_translations = vec![];
+        let mut rs_write_backs = vec![];
         let pstr = parameters.into_iter().map(|prop|{
             //ffi value should be transform as ptr or value
             let is_string_param = is_string_type(&prop.type_str);
@@ -607,19 +1801,38 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 //can modify
                 if !prop.const_param && prop.ref_param{
                     rs_string_translations.push(format!("string_2_rstr!({}, {});", prop.name, prop.name));
-                    rs_ffi_parameters.push(format!("RefString"));
-                    rs_parameters.push(format!("{}", prop.name));
+                    rs_ffi_parameters.push("RefString".to_string());
+                    rs_parameters.push(prop.name.to_string());
                     rs_fn_parameters.push(format!("{}: &mut String", prop.name));
                     format!("RefString {}", prop.name)
                 }
                 else{
                     rs_string_translations.push(format!("string_2_cstr!({}, {});", prop.name, prop.name));
-                    rs_ffi_parameters.push(format!("NativeString"));
-                    rs_parameters.push(format!("{}", prop.name));
+                    rs_ffi_parameters.push("NativeString".to_string());
+                    rs_parameters.push(prop.name.to_string());
                     rs_fn_parameters.push(format!("{}: &str", prop.name));
                     format!("NativeString {}", prop.name)
                 }
             }
+            else if let Some(container) = FfiContainer::parse(prop.container.as_ref(), engine, settings){
+                //can modify
+                let mutable = !prop.const_param && prop.ref_param;
+                let ffi_type = if mutable{ "RefArray" } else { "NativeArray" };
+                container.ffi_names(&prop.name).iter().for_each(|_| rs_ffi_parameters.push(ffi_type.to_string()));
+                rs_string_translations.append(&mut container.rs_locals(&prop.name, mutable));
+                rs_parameters.append(&mut container.rs_args(&prop.name, mutable));
+                if mutable{
+                    rs_write_backs.extend(container.rs_write_back(&prop.name));
+                }
+                rs_fn_parameters.push(format!("{}: {}", prop.name, container.rs_param_type(mutable)));
+                container.cpp_params(&prop.name, mutable)
+            }
+            else if let Some(smart) = SmartPtrType::parse(&prop.type_str, engine, settings){
+                rs_ffi_parameters.push(smart.rs_ffi_type());
+                rs_parameters.push(smart.rs_to_ffi(&prop.name));
+                rs_fn_parameters.push(format!("{}: {}", prop.name, smart.rs_param_type()));
+                format!("{} {}", smart.cpp_ffi_type(), prop.name)
+            }
             else{
                 let rs_tag;
                 let mut ffi_tag: &str = "";
@@ -675,7 +1888,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 let rs_param_type_name = if void_ptr{"c_void"}else if exported_type{ts.name.as_str()} else {ts.alis.as_str()};
                 rs_ffi_parameters.push(format!("{}{}", ffi_tag, ts.alis));
                 if is_primary(&prop.type_str, settings) || is_wrapper_type(&prop.type_str, settings){
-                    rs_parameters.push(format!("{}", prop.name));
+                    rs_parameters.push(prop.name.to_string());
                 }
                 else{
                     match (exported_type, prop.ptr_param | prop.ref_param, opaque) {
@@ -686,7 +1899,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                             rs_parameters.push(format!("{}.inner()", prop.name));
                         }
                         _ => {
-                            rs_parameters.push(format!("{}", prop.name));
+                            rs_parameters.push(prop.name.to_string());
                         }
                     }
                 }
@@ -695,26 +1908,31 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
             }
         }).collect::<Vec<_>>();
         let mut full_proper = pstr.clone();
+        if let Some(container) = &container_ret{
+            full_proper.push(container.cpp_params("out_result", true));
+            container.ffi_names("out_result").iter().for_each(|_| rs_ffi_parameters.push("RefArray".to_string()));
+            rs_parameters.append(&mut container.rs_result_args("out_result"));
+        }
         if !api.is_construstor && !api.is_static{
-            full_proper.insert(0, format!("void* target"));
-            rs_ffi_parameters.insert(0, format!("*mut c_void"));
+            full_proper.insert(0, "void* target".to_string());
+            rs_ffi_parameters.insert(0, "*mut c_void".to_string());
             //rust "this"
             if opaque{
-                rs_parameters.insert(0, format!("self.inner"));
+                rs_parameters.insert(0, "self.inner".to_string());
             }
             else{
                 if api.is_const{
-                    rs_parameters.insert(0, format!("self as *const Self as *mut c_void"));
+                    rs_parameters.insert(0, "self as *const Self as *mut c_void".to_string());
                 }
                 else{
-                    rs_parameters.insert(0, format!("self as *mut Self as *mut c_void"));
+                    rs_parameters.insert(0, "self as *mut Self as *mut c_void".to_string());
                 }
             }
             if api.is_const{
-                rs_fn_parameters.insert(0, format!("&self"));
+                rs_fn_parameters.insert(0, "&self".to_string());
             }
             else{
-                rs_fn_parameters.insert(0, format!("&mut self"));
+                rs_fn_parameters.insert(0, "&mut self".to_string());
             }
         }
         let mut c_api_local_parameters = vec![];
@@ -750,11 +1968,30 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 c_api_local_parameters.push(local_var);
                 format!("{ref_tag}{param_name}")
             }   
+            else if let Some(container) = FfiContainer::parse(p.container.as_ref(), engine, settings){
+                let param_name = format!("container{idx}");
+                c_api_local_parameters.push(format!("auto {param_name} = {};", container.to_native(&p.name)));
+                //none const ref container will write back into rust collection
+                if !p.const_param && p.ref_param{
+                    c_api_modifiers.push(container.reset_rust(&param_name, &p.name));
+                }
+                param_name
+            }
+            else if let Some(smart) = SmartPtrType::parse(&p.type_str, engine, settings){
+                smart.cpp_from_ffi(&p.name)
+            }
             else{
                 //wrapper types      
                 if is_wrapper_type(&p.type_str, settings){
                     // let type_str = get_wrapper_type(&p.type_str, settings);
-                    if p.ptr_param{
+                    //none const ref is called with a converted local, which is written back into rust after the call
+                    if p.ref_param && !p.const_param{
+                        let param_name = format!("wrapper{idx}");
+                        c_api_local_parameters.push(format!("auto {param_name} = To{}({});", p.type_str, p.name));
+                        c_api_modifiers.push(format!("{} = To{}({param_name});", p.name, get_wrapper_type(&p.type_str, settings)));
+                        param_name
+                    }
+                    else if p.ptr_param{
                         format!("To{}(*{})", p.type_str, p.name)
                     }
                     else{
@@ -784,7 +2021,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 c_api_local_parameters.iter().for_each(|lp| func_block.push(format!("\t\t{lp}")));
             }
             func_block.push(format!("\t\tauto _this = new {class_name}({parameter_name_list});"));
-            if c_api_modifiers.len() > 0{
+            if !c_api_modifiers.is_empty(){
                 c_api_modifiers.iter().for_each(|lp| func_block.push(format!("\t\t{lp}")));
             }
             func_block.push("\t\treturn _this;\r\n\t}".to_string());
@@ -806,30 +2043,36 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
             };
             let api_name = format!("uapi_{}_{}", class_name, designed_api_name);
             let return_flag = if cpp_ret == "void"{""}else{"return "};
-            let (wrapper_result_start, wrapper_result_end) = if !is_string_ret && is_wrapper_type(&api.rc_type, settings){
+            let (wrapper_result_start, wrapper_result_end) = if let Some(smart) = &smart_ret{
+                smart.cpp_to_ffi()
+            }
+            else if !is_string_ret && is_wrapper_type(&api.rc_type, settings){
                 let ret_name = get_wrapper_type(&api.rc_type, settings);
-                (format!("To{ret_name}("), format!(")"))
+                (format!("To{ret_name}("), ")".to_string())
             }
             else{
                 if is_string_ret{
                     (
                         match api.rc_type.as_str() {
-                            "FString"   => format!("FString2Utf8("),
-                            _/*"FText"*/=> format!("FText2Utf8("),
+                            "FString"   => "FString2Utf8(".to_string(),
+                            _/*"FText"*/=> "FText2Utf8(".to_string(),
                         }, 
-                        format!(")")
+                        ")".to_string()
                     )
                 }
                 else{
-                    (format!(""), format!(""))
+                    (String::new(), String::new())
                 }
             };
-            let result_local = if cpp_ret == "void"{""}else{"auto result = "};
+            let result_local = if container_ret.is_some(){"const auto& result = "} else if cpp_ret == "void"{""}else{"auto result = "};
+            if let Some(container) = &container_ret{
+                c_api_modifiers.push(container.reset_rust("result", "out_result"));
+            }
             let return_result = if cpp_ret == "void"{""}else{"result;"};
             //function 
             func_block.push(format!("\t{cpp_ret} {api_name}({}){{", full_proper.join(", ")));
             //local parameters
-            if c_api_local_parameters.len() > 0{
+            if !c_api_local_parameters.is_empty(){
                 c_api_local_parameters.iter().for_each(|lp| func_block.push(format!("\t\t{lp}")));
             }
             //static api
@@ -849,7 +2092,7 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                     func_block.push(format!("\t\t{result_local}{wrapper_result_start}{result_caster}(({class_name}*)target)->{}({parameter_name_list}){wrapper_result_end};", api.name));                    
                 }
             }
-            if c_api_modifiers.len() > 0{
+            if !c_api_modifiers.is_empty(){
                 c_api_modifiers.iter().for_each(|lp| func_block.push(format!("\t\t{lp}")));
             }
             //return flags
@@ -881,6 +2124,11 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 ref_flag_tail = ")";
                 "char_str_2_string(".to_string()
             }
+            else if let Some(smart) = &smart_ret{
+                let (begin, end) = smart.rs_from_ffi();
+                ref_flag_tail = end;
+                begin
+            }
             else{
                 if opaque_ret && export_type(&api.rc_type, settings){
                     ref_flag_tail = ")";
@@ -891,36 +2139,36 @@ fn parse_functions(engine: &Engine, class: &UnrealClass, generator: &mut CodeGen
                 }
             }
         }; 
-        //rust getter ffi api
-        let callback_name = format!("{}_{}Invoker", class_name, designed_api_name);
-        let callback_handler = format!("{callback_name}Handler");
-        let ffi_api_name = format!("set_{}_{}_handler", class_name, designed_api_name);        
-        let handler_code = format!(r#"
-    type {callback_name} = unsafe extern "C" fn({}){};
-    pub(super) static mut {callback_handler}: Option<{callback_name}> = None;
-    #[no_mangle]
-    extern "C" fn {ffi_api_name}(handler: {callback_name}){{
-        unsafe{{ {callback_handler} = Some(handler) }};
-    }}"#, rs_ffi_parameters.join(", "), rs_ret_origin);
-        //get handler
-        generator.rs_ffis.push(handler_code);
-
-        // cpp register         
-        generator.api_defines.push(format!(r#"
-using {cpp_api_name}Fn = void(*)({cpp_ret}(*)({}));"#, full_proper.join(",")));
-
-        generator.registers.push(format!(r#"
-    auto const api{cpp_api_name} = ({cpp_api_name}Fn)plugin->GetDllExport(TEXT("{ffi_api_name}\0"));
-    if(api{cpp_api_name}){{
-        api{cpp_api_name}(&{cpp_api_name});
-    }}"#));  
+        //native function table entry
+        let invoker = generator.push_binding(&cpp_api_name, format!("unsafe extern \"C\" fn({}){}", rs_ffi_parameters.join(", "), rs_ret_origin));
         //rust member function
-        let mut rs_block: Vec<String> = vec!["\t#[inline]".to_string()];
-        rs_block.push(format!("\tpub fn {designed_api_name}{lifetime_tag}({}){rs_ret_liftime}{{", rs_fn_parameters.join(", ")));
-        if rs_string_translations.len() > 0{
+        let mut rs_block: Vec<String> = rs_docs(&api.specifiers, "\t");
+        rs_block.push("\t#[inline]".to_string());
+        rs_block.push(format!("\tpub fn {designed_api_name}{lifetime_tag}({}){}{{", rs_fn_parameters.join(", "), generator.rs_ret(&rs_ret_liftime)));
+        if !rs_string_translations.is_empty(){
             rs_string_translations.iter().for_each(|trans| rs_block.push(format!("\t\t{trans}")));
         }
-        rs_block.push(format!("\t\tunsafe{{ {ref_flag}{callback_handler}.as_ref().unwrap()({}){ref_flag_tail} }}", rs_parameters.join(", ")));
+        let call = format!("unsafe{{ {ref_flag}{invoker}({}){ref_flag_tail} }}", rs_parameters.join(", "));
+        if let Some(container) = &container_ret{
+            container.rs_result_locals("out_result").iter().for_each(|local| rs_block.push(format!("\t\t{local}")));
+            rs_block.push(format!("\t\t{call};"));
+            rs_write_backs.iter().for_each(|write_back| rs_block.push(format!("\t\t{write_back}")));
+            rs_block.push(format!("\t\t{}", generator.rs_ok(&container.rs_collect("out_result"))));
+        }
+        else if !rs_write_backs.is_empty(){
+            let result_local = if rs_ret_liftime.is_empty(){ "" } else { "let result = " };
+            rs_block.push(format!("\t\t{result_local}{call};"));
+            rs_write_backs.iter().for_each(|write_back| rs_block.push(format!("\t\t{write_back}")));
+            if !rs_ret_liftime.is_empty(){
+                rs_block.push(format!("\t\t{}", generator.rs_ok("result")));
+            }
+            else if generator.checked_bindings{
+                rs_block.push("\t\tOk(())".to_string());
+            }
+        }
+        else{
+            rs_block.push(format!("\t\t{}", generator.rs_ok(&call)));
+        }
         rs_block.push("\t}".to_string());
         generator.rs_source.push(rs_block.join("\r\n"));
     }
@@ -934,21 +2182,71 @@ fn parse_properties(engine: &Engine, class: &UnrealClass, generator: &mut CodeGe
     let cpp_class_atlas = "void";
     let rs_class_alas = if opaque {class.name.clone() + "Opaque"} else{ class.name.clone()};
     let class_to_export = settings.ExportClasses.iter().find(|c| c.class_name == class_name).unwrap();
-    for property in &class.properties {
+    for property in members(engine, &class.properties) {
         if black_field(property, settings){
             continue;
         }
         //not in white list
-        if class_to_export.fields.len() > 0 && class_to_export.fields.iter().find(|f| f.as_str() == property.name.as_str()).is_none(){
+        if !class_to_export.fields.is_empty() && class_to_export.fields.iter().find(|f| f.as_str() == property.name.as_str()).is_none(){
             continue;
         }
         //in ignore list
-        if class_to_export.fields.len() == 0 && class_to_export.ignore_fields.iter().find(|f| f.as_str() == property.name.as_str()).is_some(){
+        if class_to_export.fields.is_empty() && class_to_export.ignore_fields.iter().find(|f| f.as_str() == property.name.as_str()).is_some(){
+            continue;
+        }
+        let blueprint_only = class_to_export.fields.is_empty() && settings.blueprint_only(class_to_export);
+        if let Some(delegate) = engine.delegates.iter().find(|d| d.multicast && d.name == property.type_str){
+            //BlueprintAssignable can be bound and BlueprintCallable can be broadcasted in blueprint
+            if property.is_static || property.is_ptr || (blueprint_only && !property.specifiers.has("BlueprintAssignable")){
+                continue;
+            }
+            match FfiDelegate::parse(delegate, engine, settings) {
+                Some(delegate) => {
+                    let broadcast = !blueprint_only || property.specifiers.has("BlueprintCallable");
+                    gen_delegate_property(class, property, &delegate, broadcast, generator);
+                },
+                None => println!("delegate {} of {}.{} has unsupported parameters", delegate.name, class_name, property.name),
+            }
+            continue;
+        }
+        //not blueprint visible
+        if blueprint_only && !property.specifiers.has("BlueprintReadWrite") && !property.specifiers.has("BlueprintReadOnly"){
+            continue;
+        }
+        //getter only
+        let read_only = blueprint_only && property.specifiers.has("BlueprintReadOnly");
+        if property.array_dim > 0{
+            if !property.is_const && !property.is_static && !property.is_ptr && (is_primary(&property.type_str, settings) || is_wrapper_type(&property.type_str, settings)){
+                gen_array_property(class, property, read_only, generator, settings);
+            }
+            continue;
+        }
+        //one bit flags are bool, wider bit fields are exported as their integer type
+        if property.bit_value && property.bit_width == 1{
+            if !property.is_const && !property.is_static{
+                gen_bit_property(class, property, read_only, generator);
+            }
+            continue;
+        }
+        if let Some(container) = FfiContainer::parse(property.container.as_ref(), engine, settings){
+            if !property.is_const && !property.is_static && !property.is_ptr{
+                gen_container_property(class, property, &container, read_only, generator);
+            }
+            continue;
+        }
+        if let Some(smart) = SmartPtrType::parse(&property.type_str, engine, settings){
+            if !property.is_const && !property.is_static && !property.is_ptr{
+                gen_smart_ptr_property(class, property, &smart, read_only, generator);
+            }
             continue;
         }
         if property.is_const || property.is_static || (!should_export_property(engine, property) && !is_wrapper_type(&property.type_str, settings)){
             continue;
         }
+        //named field of unnamed record, the type can not be named in c++
+        if unnamed_record(engine, property).is_some(){
+            continue;
+        }
 
         let is_string_type = is_string_type(&property.type_str);
         //ptr string has lifetime problem
@@ -1007,59 +2305,26 @@ fn parse_properties(engine: &Engine, class: &UnrealClass, generator: &mut CodeGe
         //
         //cpp setter pai
         let set_cpp_name = format!("set_{class_name}_{}", property.name);
-        let content = format!(r#"
+        if !read_only{
+            let content = format!(r#"
     void {set_cpp_name}({cpp_class_atlas}* target, {} value){{ (({class_name}*)target) -> {} = {string_set_caster_begin}value{string_caster_end};}};"#,
-    ret_type, property.name);
+        ret_type, property.name);
     
-        generator.source.push(content);
+            generator.source.push(content);
+        }
 
         //rust code
 
         //pub type IterateActorsFn = unsafe extern "C" fn(array: *mut *mut AActorOpaque, len: *mut u64);
         //rust getter ffi api
-        let callback_name = format!("Get{}Invoker", property.name);
-        let callback_handler_get = format!("{callback_name}Handler");
-        let api_name = format!("set_{}{}_get_handler", class_name, property.name);
-        let handler_code = format!(r#"
-    type {callback_name} = unsafe extern "C" fn(target: *mut {rs_class_alas}) -> {};
-    pub(super) static mut {callback_handler_get}: Option<{callback_name}> = None;
-    #[no_mangle]
-    extern "C" fn {api_name}(handler: {callback_name}){{
-        unsafe{{ {callback_handler_get} = Some(handler) }};
-    }}"#, rs_type);
-        //get handler
-        generator.rs_ffis.push(handler_code);
-        //get cpp register
-        //using EntryUnrealBindingsFn = uint32_t(*)(UnrealBindings bindings, RustBindings *rust_bindings);
-        generator.api_defines.push(format!(r#"
-using {api_name}Fn = void(*)({}(*)({cpp_class_atlas}* target));"#, ret_type));
-
-    generator.registers.push(format!(r#"
-    auto const api{api_name} = ({api_name}Fn)plugin->GetDllExport(TEXT("{api_name}\0"));
-    if(api{api_name}){{
-        api{api_name}(&{get_cpp_name});
-    }}"#));
-
-        //rust setter ffi block
-        let callback_name = format!("Set{}Invoker", property.name);
-        let callback_handler_set = format!("{callback_name}Handler");
-        let api_name = format!("set_{}{}_set_handler", class_name, property.name);
-        let handler_code = format!(r#"
-    type {callback_name} = unsafe extern "C" fn(target: *mut {rs_class_alas}, value: {});
-    pub(super) static mut {callback_handler_set}: Option<{callback_name}> = None;
-    #[no_mangle]
-    extern "C" fn {api_name}(handler: {callback_name}){{
-        unsafe {{{callback_handler_set} = Some(handler) }};
-    }}"#, rs_type);
-        //setter
-        generator.rs_ffis.push(handler_code);
-        //cpp setter ffi api
-        generator.api_defines.push(format!(r#"
-using {api_name}Fn = void(*)(void(*)({cpp_class_atlas}* target, {} value));"#, ret_type));
-        generator.registers.push(format!(r#"    auto const api{api_name} = ({api_name}Fn)plugin->GetDllExport(TEXT("{api_name}\0"));
-    if(api{api_name}){{
-        api{api_name}(&{set_cpp_name});
-    }}"#));
+        let callback_handler_get = generator.push_binding(&get_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}) -> {rs_type}"));
+        //rust setter ffi api
+        let callback_handler_set = if !read_only{
+            generator.push_binding(&set_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, value: {rs_type})"))
+        }
+        else{
+            String::new()
+        };
         //if is string, should be convert to String
         //not supported yet
         let (getter_caster, setter_caster, get_caster_end, set_caster_end) = if is_string_type{
@@ -1082,16 +2347,512 @@ using {api_name}Fn = void(*)(void(*)({cpp_class_atlas}* target, {} value));"#, r
             ("".to_string(), "".to_string(), "".to_string(), "".to_string())
         };
         //rust getter/setter source code
+        let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
         generator.rs_source.push(format!(r#"
+{docs}    #[inline]
+    pub fn get_{}(&self){}{{
+        {}
+    }}"#, property.name, generator.rs_ret(&format!(" -> {rs_type}")),
+    generator.rs_ok(&format!("unsafe{{ {getter_caster}{callback_handler_get}(self.inner){get_caster_end} }}"))));
+        if !read_only{
+            generator.rs_source.push(format!(r#"
     #[inline]
-    pub fn get_{}(&self) -> {}{{
-        unsafe{{ {getter_caster}{callback_handler_get}.as_ref().unwrap()(self.inner){get_caster_end} }}
-    }}"#, property.name, rs_type));
+    pub fn set_{}(&mut self, value: {}){}{{
+        {}
+    }}"#, property.name, rs_type, generator.rs_ret(""),
+    generator.rs_ok(&format!("unsafe{{ {callback_handler_set}(self.inner, {setter_caster}value{set_caster_end}) }}"))));
+        }
+    }
+    Ok(())
+}
+///TArray/TSet/TMap field, getter copies into rust collection and setter copies from it
+fn gen_container_property(class: &UnrealClass, property: &CppProperty, container: &FfiContainer, read_only: bool, generator: &mut CodeGenerator){
+    let class_name = class.name.as_str();
+    let field = property.name.as_str();
+    let rs_class_alas = format!("{class_name}Opaque");
+    let rs_type = container.rs_type();
+    //cpp getter api
+    let get_cpp_name = format!("get_{class_name}_{field}");
+    let get_params = container.cpp_params("out_result", true);
+    generator.source.push(format!(r#"
+    void {get_cpp_name}(void* target, {get_params}) {{ {} }};"#,
+    container.reset_rust(&format!("(({class_name}*)target) -> {field}"), "out_result")));
+    let rs_get_params = container.ffi_names("out_result").iter().map(|name| format!(", {name}: RefArray")).collect::<String>();
+    let callback_handler_get = generator.push_binding(&get_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}{rs_get_params})"));
+    let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
+    let result_locals = container.rs_result_locals("out_result").iter().map(|local| format!("\n        {local}")).collect::<String>();
+    generator.rs_source.push(format!(r#"
+{docs}    #[inline]
+    pub fn get_{field}(&self){}{{{result_locals}
+        unsafe{{ {callback_handler_get}(self.inner, {}) }};
+        {}
+    }}"#, generator.rs_ret(&format!(" -> {rs_type}")), container.rs_result_args("out_result").join(", "), generator.rs_ok(&container.rs_collect("out_result"))));
+    if read_only{
+        return;
+    }
+    //cpp setter api
+    let set_cpp_name = format!("set_{class_name}_{field}");
+    let set_params = container.cpp_params("value", false);
+    generator.source.push(format!(r#"
+    void {set_cpp_name}(void* target, {set_params}){{ (({class_name}*)target) -> {field} = {};}};"#,
+    container.to_native("value")));
+    let rs_set_params = container.ffi_names("value").iter().map(|name| format!(", {name}: NativeArray")).collect::<String>();
+    let callback_handler_set = generator.push_binding(&set_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}{rs_set_params})"));
+    let locals = container.rs_locals("value", false).iter().map(|local| format!("\n        {local}")).collect::<String>();
+    generator.rs_source.push(format!(r#"
+    #[inline]
+    pub fn set_{field}(&mut self, value: {}){}{{{locals}
+        {}
+    }}"#, container.rs_param_type(false), generator.rs_ret(""),
+    generator.rs_ok(&format!("unsafe{{ {callback_handler_set}(self.inner, {}) }}", container.rs_args("value", false).join(", ")))));
+}
+///static array field `T Field[N]`, accessed by index
+fn gen_array_property(class: &UnrealClass, property: &CppProperty, read_only: bool, generator: &mut CodeGenerator, settings: &CustomSettings){
+    let class_name = class.name.as_str();
+    let field = property.name.as_str();
+    let dim = property.array_dim;
+    let rs_class_alas = format!("{class_name}Opaque");
+    let (cpp_type, rs_type, get_caster, set_caster) = if is_wrapper_type(&property.type_str, settings){
+        let wrapper = get_wrapper_type(&property.type_str, settings);
+        (wrapper.clone(), wrapper.clone(), format!("To{wrapper}"), format!("To{}", property.type_str))
+    }
+    else{
+        (property.type_str.clone(), property.r_type.clone(), String::new(), String::new())
+    };
+    //cpp getter api
+    let get_cpp_name = format!("get_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    {cpp_type} {get_cpp_name}(void* target, uint32 index) {{ return {get_caster}((({class_name}*)target) -> {field}[index]); }};"#));
+    let callback_handler_get = generator.push_binding(&get_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, index: u32) -> {rs_type}"));
+    let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
+    generator.rs_source.push(format!(r#"
+{docs}    ///panics if index >= {dim}
+    #[inline]
+    pub fn get_{field}(&self, index: usize){}{{
+        assert!(index < {dim}, "index out of {field}[{dim}]");
+        {}
+    }}"#, generator.rs_ret(&format!(" -> {rs_type}")), generator.rs_ok(&format!("unsafe{{ {callback_handler_get}(self.inner, index as u32) }}"))));
+    if read_only{
+        return;
+    }
+    //cpp setter api
+    let set_cpp_name = format!("set_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    void {set_cpp_name}(void* target, uint32 index, {cpp_type} value){{ (({class_name}*)target) -> {field}[index] = {set_caster}(value); }};"#));
+    let callback_handler_set = generator.push_binding(&set_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, index: u32, value: {rs_type})"));
+    generator.rs_source.push(format!(r#"
+    ///panics if index >= {dim}
+    #[inline]
+    pub fn set_{field}(&mut self, index: usize, value: {rs_type}){}{{
+        assert!(index < {dim}, "index out of {field}[{dim}]");
+        {}
+    }}"#, generator.rs_ret(""), generator.rs_ok(&format!("unsafe{{ {callback_handler_set}(self.inner, index as u32, value) }}"))));
+}
+///one bit flag field, bit field can not be addressed so it is accessed by value
+fn gen_bit_property(class: &UnrealClass, property: &CppProperty, read_only: bool, generator: &mut CodeGenerator){
+    let class_name = class.name.as_str();
+    let field = property.name.as_str();
+    let rs_class_alas = format!("{class_name}Opaque");
+    //cpp getter api
+    let get_cpp_name = format!("get_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    bool {get_cpp_name}(void* target) {{ return (({class_name}*)target) -> {field} != 0; }};"#));
+    let callback_handler_get = generator.push_binding(&get_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}) -> bool"));
+    let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
+    generator.rs_source.push(format!(r#"
+{docs}    #[inline]
+    pub fn get_{field}(&self){}{{
+        {}
+    }}"#, generator.rs_ret(" -> bool"), generator.rs_ok(&format!("unsafe{{ {callback_handler_get}(self.inner) }}"))));
+    if read_only{
+        return;
+    }
+    //cpp setter api
+    let set_cpp_name = format!("set_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    void {set_cpp_name}(void* target, bool value){{ (({class_name}*)target) -> {field} = value ? 1 : 0; }};"#));
+    let callback_handler_set = generator.push_binding(&set_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, value: bool)"));
+    generator.rs_source.push(format!(r#"
+    #[inline]
+    pub fn set_{field}(&mut self, value: bool){}{{
+        {}
+    }}"#, generator.rs_ret(""), generator.rs_ok(&format!("unsafe{{ {callback_handler_set}(self.inner, value) }}"))));
+}
+///smart pointer field, getter unwraps into object or weak handle
+fn gen_smart_ptr_property(class: &UnrealClass, property: &CppProperty, smart: &SmartPtrType, read_only: bool, generator: &mut CodeGenerator){
+    let class_name = class.name.as_str();
+    let field = property.name.as_str();
+    let rs_class_alas = format!("{class_name}Opaque");
+    let (cpp_ffi_type, rs_ffi_type) = (smart.cpp_ffi_type(), smart.rs_ffi_type());
+    //cpp getter api
+    let get_cpp_name = format!("get_{class_name}_{field}");
+    let (caster_begin, caster_end) = smart.cpp_to_ffi();
+    generator.source.push(format!(r#"
+    {cpp_ffi_type} {get_cpp_name}(void* target) {{ return {caster_begin}(({class_name}*)target) -> {field}{caster_end}; }};"#));
+    let callback_handler_get = generator.push_binding(&get_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}) -> {rs_ffi_type}"));
+    let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
+    let (rs_caster_begin, rs_caster_end) = smart.rs_from_ffi();
+    generator.rs_source.push(format!(r#"
+{docs}    #[inline]
+    pub fn get_{field}(&self){}{{
+        {}
+    }}"#, generator.rs_ret(&format!(" -> {}", smart.rs_type())),
+    generator.rs_ok(&format!("unsafe{{ {rs_caster_begin}{callback_handler_get}(self.inner){rs_caster_end} }}"))));
+    //soft ptr is null until the asset is loaded
+    if smart.kind == SmartPtr::SoftObjectPtr{
+        let pointee = smart.pointee.as_str();
+        let path_cpp_name = format!("get_{class_name}_{field}_path");
+        let load_cpp_name = format!("load_{class_name}_{field}");
+        generator.source.push(format!(r#"
+    const char* {path_cpp_name}(void* target) {{ return FString2Utf8((({class_name}*)target) -> {field}.ToSoftObjectPath().ToString()); }};
+    void* {load_cpp_name}(void* target) {{ return (void*)(({class_name}*)target) -> {field}.LoadSynchronous(); }};"#));
+        let callback_handler_path = generator.push_binding(&path_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}) -> *const std::os::raw::c_char"));
+        let callback_handler_load = generator.push_binding(&load_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}) -> *mut c_void"));
         generator.rs_source.push(format!(r#"
+    ///soft object path of {field}, valid while the asset is not loaded
     #[inline]
-    pub fn set_{}(&mut self, value: {}){{
-        unsafe{{ {callback_handler_set}.as_ref().unwrap()(self.inner, {setter_caster}value{set_caster_end}) }}
-    }}"#, property.name, rs_type));
+    pub fn get_{field}_path(&self){}{{
+        {}
+    }}
+    ///load the asset of {field} synchronously if it is not loaded
+    #[inline]
+    pub fn load_{field}(&self){}{{
+        {}
+    }}"#, generator.rs_ret(" -> String"),
+        generator.rs_ok(&format!("char_str_2_string(unsafe{{ {callback_handler_path}(self.inner) }})")),
+        generator.rs_ret(&format!(" -> Option<{pointee}>")),
+        generator.rs_ok(&format!("unsafe{{ {pointee}::from_ptr({callback_handler_load}(self.inner)) }}"))));
     }
-    Ok(())
-}
\ No newline at end of file
+    if read_only{
+        return;
+    }
+    //cpp setter api
+    let set_cpp_name = format!("set_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    void {set_cpp_name}(void* target, {cpp_ffi_type} value){{ (({class_name}*)target) -> {field} = {};}};"#,
+    smart.cpp_from_ffi("value")));
+    let callback_handler_set = generator.push_binding(&set_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, value: {rs_ffi_type})"));
+    generator.rs_source.push(format!(r#"
+    #[inline]
+    pub fn set_{field}(&mut self, value: {}){}{{
+        {}
+    }}"#, smart.rs_param_type(), generator.rs_ret(""),
+    generator.rs_ok(&format!("unsafe{{ {callback_handler_set}(self.inner, {}) }}", smart.rs_to_ffi("value")))));
+}
+///multicast delegate field, rust closure is bound by a trampoline object and unbound by its handle
+fn gen_delegate_property(class: &UnrealClass, property: &CppProperty, delegate: &FfiDelegate, broadcast: bool, generator: &mut CodeGenerator){
+    let class_name = class.name.as_str();
+    let field = property.name.as_str();
+    let delegate_name = delegate.delegate.name.as_str();
+    let rs_class_alas = format!("{class_name}Opaque");
+    let (cpp_ffi_params, rs_ffi_params) = (delegate.cpp_ffi_params(), delegate.rs_ffi_params());
+    //cpp forwarder from params of broadcast to rust callback
+    let forward_name = format!("forward_{delegate_name}");
+    if !generator.forwarded_delegates.iter().any(|name| name == delegate_name){
+        generator.forwarded_delegates.push(delegate_name.to_string());
+        let ffi_types = delegate.params.iter().map(|(_, element)| format!(", {}", element.ffi_type)).collect::<String>();
+        let args = delegate.params.iter().map(|(name, element)| format!(", {}", element.cpp_to_ffi(&format!("args->{name}")))).collect::<String>();
+        let (parms, parms_local) = if delegate.params.is_empty(){
+            (String::new(), String::new())
+        }
+        else{
+            let members = delegate.params.iter().map(|(name, element)| format!(" {} {name};", element.c_type)).collect::<String>();
+            (
+                format!("\r\n    struct {delegate_name}_Parms {{{members} }};"),
+                format!("\r\n        auto args = ({delegate_name}_Parms*)parms;")
+            )
+        };
+        generator.source.push(format!(r#"{parms}
+    void {forward_name}(void* callback, void* closure, void* parms) {{{parms_local}
+        ((void(*)(void*{ffi_types}))callback)(closure{args});
+    }};"#));
+    }
+    let function_name = "GET_FUNCTION_NAME_CHECKED(URustDelegate, Invoke)";
+    //cpp add api
+    let add_cpp_name = format!("add_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    void* {add_cpp_name}(void* target, void* closure, void* callback, drop_rust_closure_handler drop) {{
+        auto trampoline = NewRustDelegate(closure, callback, &{forward_name}, drop);
+        {} delegate;
+        delegate.BindUFunction(trampoline, {function_name});
+        (({class_name}*)target) -> {field}.Add(delegate);
+        return trampoline;
+    }};"#, delegate.cpp_delegate_type()));
+    let add_handler = generator.push_binding(&add_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, closure: *mut c_void, callback: *mut c_void, drop: unsafe extern \"C\" fn(*mut c_void)) -> *mut c_void"));
+    //cpp remove api
+    let remove_cpp_name = format!("remove_{class_name}_{field}");
+    generator.source.push(format!(r#"
+    bool {remove_cpp_name}(void* target, void* handle) {{
+        auto& field = (({class_name}*)target) -> {field};
+        //handle of another field or object
+        if (!field.Contains((URustDelegate*)handle, {function_name})) {{
+            return false;
+        }}
+        field.Remove((URustDelegate*)handle, {function_name});
+        ReleaseRustDelegate(handle);
+        return true;
+    }};"#));
+    let remove_handler = generator.push_binding(&remove_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}, handle: *mut c_void) -> bool"));
+    //rust subscription
+    let closure_type = delegate.rs_closure_type();
+    let closure_args = delegate.params.iter().map(|(name, element)| element.rs_from_ffi(name)).collect::<Vec<_>>().join(", ");
+    let docs = rs_docs(&property.specifiers, "    ").into_iter().map(|doc| doc + "\n").collect::<String>();
+    generator.rs_source.push(format!(r#"
+{docs}    pub fn add_{field}<Callback: {closure_type} + 'static>(&mut self, callback: Callback){}{{
+        unsafe extern "C" fn invoke<Callback: {closure_type}>(closure: *mut c_void{rs_ffi_params}){{
+            let callback = unsafe{{ &mut *(closure as *mut Callback) }};
+            //panic(e.g. of unregistered bindings) must not unwind into unreal
+            if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback({closure_args}))).is_err(){{
+                error!("rust callback of {class_name}::{field} panicked");
+            }}
+        }}
+        let add = {add_handler};
+        let closure = Box::into_raw(Box::new(callback)) as *mut c_void;
+        {}
+    }}
+    ///the handle is returned back if it is not bound to this field
+    #[inline]
+    pub fn remove_{field}(&mut self, handle: DelegateHandle){}{{
+        {}
+    }}"#, generator.rs_ret(" -> DelegateHandle"),
+    generator.rs_ok("DelegateHandle{ inner: unsafe{ add(self.inner, closure, invoke::<Callback> as *mut c_void, drop_closure::<Callback>) } }"),
+    generator.rs_ret(" -> Result<(), DelegateHandle>"),
+    generator.rs_ok(&format!("if unsafe{{ {remove_handler}(self.inner, handle.inner) }} {{ Ok(()) }} else {{ Err(handle) }}"))));
+    if !broadcast{
+        return;
+    }
+    //cpp broadcast api
+    let broadcast_cpp_name = format!("broadcast_{class_name}_{field}");
+    let native_args = delegate.params.iter().map(|(name, element)| element.cpp_from_ffi(name)).collect::<Vec<_>>().join(", ");
+    generator.source.push(format!(r#"
+    void {broadcast_cpp_name}(void* target{cpp_ffi_params}) {{ (({class_name}*)target) -> {field}.Broadcast({native_args}); }};"#));
+    let broadcast_handler = generator.push_binding(&broadcast_cpp_name, format!("unsafe extern \"C\" fn(target: *mut {rs_class_alas}{rs_ffi_params})"));
+    let params = delegate.params.iter().map(|(name, element)| format!(", {name}: {}", element.rs_param_type())).collect::<String>();
+    let args = delegate.params.iter().map(|(name, element)| format!(", {}", element.rs_to_ffi(name))).collect::<String>();
+    generator.rs_source.push(format!(r#"
+    #[inline]
+    pub fn broadcast_{field}(&self{params}){}{{
+        {}
+    }}"#, generator.rs_ret(""), generator.rs_ok(&format!("unsafe{{ {broadcast_handler}(self.inner{args}) }}"))));
+}
+///doc comments from reflection specifiers
+fn rs_docs(specifiers: &Specifiers, indent: &str) -> Vec<String>{
+    let mut docs = vec![];
+    if let Some(tool_tip) = specifiers.meta.get("ToolTip"){
+        docs.push(format!("{indent}///{tool_tip}"));
+    }
+    if let Some(category) = specifiers.value("Category"){
+        docs.push(format!("{indent}///Category: {category}"));
+    }
+    docs
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::config::{MappedWrapper, Parameter, WrapperField};
+    fn property(name: &str, type_str: &str) -> CppProperty{
+        CppProperty{ name: name.to_string(), type_str: type_str.to_string(), ..Default::default() }
+    }
+    fn offsets(layout: &RecordLayout) -> Vec<usize>{
+        layout.offsets.iter().map(|(_, offset)| *offset).collect()
+    }
+    #[test]
+    fn struct_fields_are_aligned() {
+        let engine = Engine{ pointer_size: 8, ..Default::default() };
+        let class = UnrealClass{
+            name: "FPoint".to_string(),
+            properties: vec![
+                property("A", "int32"),
+                property("B", "double"),
+                property("C", "uint8"),
+                CppProperty{ array_dim: 4, ..property("Weights", "float") },
+                CppProperty{ is_ptr: true, ..property("Owner", "AActor") },
+            ],
+            ..Default::default()
+        };
+        let layout = computed_layout(&engine, &class, &CustomSettings::default()).unwrap();
+        assert_eq!(offsets(&layout), vec![0, 8, 16, 20, 40]);
+        assert_eq!((layout.size, layout.align), (48, 8));
+    }
+    #[test]
+    fn pointer_size_of_target() {
+        let class = UnrealClass{
+            name: "FRef".to_string(),
+            properties: vec![property("A", "uint8"), CppProperty{ is_ptr: true, ..property("Owner", "AActor") }],
+            ..Default::default()
+        };
+        let settings = CustomSettings::default();
+        let engine = Engine{ pointer_size: 4, ..Default::default() };
+        let layout = computed_layout(&engine, &class, &settings).unwrap();
+        assert_eq!(offsets(&layout), vec![0, 4]);
+        assert_eq!((layout.size, layout.align), (8, 4));
+        //unknown target
+        assert!(computed_layout(&Engine::default(), &class, &settings).is_none());
+    }
+    #[test]
+    fn union_members_start_at_zero() {
+        let engine = Engine::default();
+        let class = UnrealClass{
+            name: "FNum".to_string(),
+            is_union: true,
+            properties: vec![property("I", "int32"), property("F", "float"), CppProperty{ array_dim: 3, ..property("D", "double") }],
+            ..Default::default()
+        };
+        let layout = computed_layout(&engine, &class, &CustomSettings::default()).unwrap();
+        assert_eq!(offsets(&layout), vec![0, 0, 0]);
+        assert_eq!((layout.size, layout.align), (24, 8));
+    }
+    #[test]
+    fn anonymous_union_in_struct() {
+        let record = UnrealClass{
+            name: "FShape_Union0".to_string(),
+            is_union: true,
+            properties: vec![property("A", "float"), property("D", "double")],
+            ..Default::default()
+        };
+        let class = UnrealClass{
+            name: "FShape".to_string(),
+            properties: vec![property("Kind", "int32"), CppProperty{ anonymous: true, ..property("union0", "FShape_Union0") }],
+            ..Default::default()
+        };
+        let engine = Engine{ classes: vec![record], ..Default::default() };
+        let layout = computed_layout(&engine, &class, &CustomSettings::default()).unwrap();
+        assert_eq!(offsets(&layout), vec![0, 8]);
+        assert_eq!((layout.size, layout.align), (16, 8));
+    }
+    #[test]
+    fn records_of_named_unnamed_fields() {
+        let record = |name: &str, is_union: bool| UnrealClass{
+            name: name.to_string(),
+            is_union,
+            is_unnamed: true,
+            properties: vec![property("W", "float"), property("H", "float")],
+            ..Default::default()
+        };
+        //`union {...};` and `struct {...} Box;`
+        let class = UnrealClass{
+            name: "FBoxed".to_string(),
+            properties: vec![CppProperty{ anonymous: true, ..property("union0", "FBoxed_Union0") }, property("Box", "FBoxed_Struct1")],
+            ..Default::default()
+        };
+        let engine = Engine{ classes: vec![record("FBoxed_Union0", true), record("FBoxed_Struct1", false)], ..Default::default() };
+        let mut generator = CodeGenerator::default();
+        gen_records(&engine, &class, &mut generator, &CustomSettings::default());
+        let records = generator.rs_source[generator.default_rs_header..].iter().filter_map(|source| source.lines().nth(1)).collect::<Vec<_>>();
+        assert_eq!(records, vec!["pub union FBoxed_Union0{", "pub struct FBoxed_Struct1{"]);
+        //only anonymous members are accessed through the owner
+        assert_eq!(members(&engine, &class.properties).iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["W", "H", "Box"]);
+    }
+    #[test]
+    fn bit_fields_are_unknown() {
+        let class = UnrealClass{
+            name: "FFlags".to_string(),
+            properties: vec![property("A", "int32"), CppProperty{ bit_value: true, bit_width: 1, ..property("bFlag", "uint8") }],
+            ..Default::default()
+        };
+        assert!(computed_layout(&Engine::default(), &class, &CustomSettings::default()).is_none());
+    }
+    #[test]
+    fn only_eq_hash_elements_are_set_keys() {
+        let alias = |cpp_type: &str, rs_type: &str| TypeWrapper::Alias([cpp_type.to_string(), rs_type.to_string()]);
+        let mapped = |cpp_type: &str, rs_type: &str, derives: &[&str]| TypeWrapper::Mapped(MappedWrapper{
+            cpp_type: cpp_type.to_string(),
+            rs_type: rs_type.to_string(),
+            derives: derives.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        });
+        let settings = CustomSettings{
+            TypeWrapper: vec![alias("FVector", "Vector3"), alias("FName", "UName"), mapped("FGuid", "Uuid", &["Eq", "Hash"]), mapped("FBox", "Box3", &[])],
+            ..Default::default()
+        };
+        let engine = Engine::default();
+        let parse = |type_str: &str| FfiContainer::parse(Container::parse(type_str).as_ref(), &engine, &settings).map(|c| c.rs_type());
+        assert_eq!(parse("TSet<FName>").as_deref(), Some("HashSet<UName>"));
+        assert_eq!(parse("TSet<FGuid>").as_deref(), Some("HashSet<Uuid>"));
+        assert_eq!(parse("TMap<FName, FVector>").as_deref(), Some("HashMap<UName, Vector3>"));
+        assert!(parse("TSet<FVector>").is_none());
+        assert!(parse("TSet<FBox>").is_none());
+        assert!(parse("TMap<FVector, int32>").is_none());
+        assert!(parse("TSet<float>").is_none());
+        assert_eq!(parse("TArray<FVector>").as_deref(), Some("Vec<Vector3>"));
+    }
+    #[test]
+    fn exported_enums_are_elements() {
+        let settings = CustomSettings{ ExportEnums: vec!["ECollisionChannel".to_string(), "ETeleportType".to_string()], ..Default::default() };
+        let engine = Engine::default();
+        let element = ElementType::parse("ETeleportType::Type", &engine, &settings).unwrap();
+        assert_eq!((element.kind, element.rs_type.as_str(), element.ffi_type.as_str()), (ElementKind::Enum, "ETeleportType", "ETeleportType::Type"));
+        assert_eq!(element.cpp_to_ffi("value"), "value");
+        assert_eq!(element.cpp_from_ffi("value"), "value");
+        assert!(ElementType::parse("EOther", &engine, &settings).is_none());
+        let parse = |type_str: &str| FfiContainer::parse(Container::parse(type_str).as_ref(), &engine, &settings).map(|c| c.rs_type());
+        assert_eq!(parse("TArray<ECollisionChannel>").as_deref(), Some("Vec<ECollisionChannel>"));
+        assert_eq!(parse("TMap<int32, ECollisionChannel>").as_deref(), Some("HashMap<i32, ECollisionChannel>"));
+        //generated enums do not derive Hash
+        assert!(parse("TSet<ECollisionChannel>").is_none());
+        assert!(parse("TMap<ECollisionChannel, int32>").is_none());
+        //delegate and override parameters
+        let delegate = CppDelegate::parse("DECLARE_DYNAMIC_MULTICAST_DELEGATE_TwoParams", "FOnTeleport", "ETeleportType, Type, int32, Count").unwrap();
+        let ffi_delegate = FfiDelegate::parse(&delegate, &engine, &settings).unwrap();
+        assert_eq!(ffi_delegate.params[0].1.kind, ElementKind::Enum);
+        let api = CppApi{
+            name: "SetChannel".to_string(),
+            rc_type: "ECollisionChannel".to_string(),
+            parameters: vec![Parameter::from_decl("ECollisionChannel", "Channel")],
+            ..Default::default()
+        };
+        let override_api = OverrideApi::parse(&api, &engine, &settings).unwrap();
+        assert_eq!(override_api.params[0].2.kind, ElementKind::Enum);
+        assert_eq!(override_api.ret.map(|ret| ret.kind), Some(ElementKind::Enum));
+    }
+    #[test]
+    fn wrapper_layouts_from_wrapper_rs_and_mapping() {
+        let wrapper_source = r#"
+pub type Real = f32;
+pub type RealVec3 = glam::Vec3;
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+pub struct Vector3
+{
+    /** x, y and z are Real */
+    pub x: Real,
+    pub y: Real, pub z: Real,
+}
+pub type Vector4 = [Real; 4];
+#[repr(u32)]
+pub enum ShapeType{ Box, Sphere }
+#[repr(C)]
+pub union ShapeUnion{ pub extent: Vector3, pub radius: f32 }
+#[repr(C)]
+pub struct Shape{ pub kind: ShapeType, pub shape: ShapeUnion, pub owner: *mut std::ffi::c_void }
+#[repr(C)]
+pub struct WeakObjectPtr<T>{ index: i32, serial: i32, marker: std::marker::PhantomData<T> }
+impl Vector3{
+    pub const ZERO: Self = Self{ x: 0.0, y: 0.0, z: 0.0 };
+}
+"#;
+        let alias = |cpp_type: &str, rs_type: &str| TypeWrapper::Alias([cpp_type.to_string(), rs_type.to_string()]);
+        let field = |name: &str, rs_type: &str| WrapperField{ name: name.to_string(), cpp_field: name.to_uppercase(), rs_type: rs_type.to_string() };
+        let span = TypeWrapper::Mapped(MappedWrapper{
+            cpp_type: "FSpan".to_string(),
+            rs_type: "Span".to_string(),
+            fields: vec![field("v", "Vector3"), field("len", "u8")],
+            ..Default::default()
+        });
+        let settings = CustomSettings{
+            TypeWrapper: vec![alias("FVector", "Vector3"), alias("FVector4", "Vector4"), alias("FShape", "Shape"), alias("FWeak", "WeakObjectPtr"), alias("FVec", "RealVec3"), span],
+            LargeWorldCoordinates: true,
+            ..Default::default()
+        };
+        let layouts = wrapper_layouts(wrapper_source, 8, &settings);
+        //Real follows LargeWorldCoordinates instead of wrapper.rs
+        assert_eq!(layouts.get("Vector3"), Some(&(24, 8)));
+        assert_eq!(layouts.get("Vector4"), Some(&(32, 8)));
+        assert_eq!(layouts.get("Shape"), Some(&(40, 8)));
+        assert_eq!(layouts.get("Span"), Some(&(32, 8)));
+        assert!(!layouts.contains_key("WeakObjectPtr"));
+        assert!(!layouts.contains_key("RealVec3"));
+        //types of unparsable wrapper.rs are unknown, so are mapped wrappers with fields of them
+        assert!(wrapper_layouts("pub struct Vector3 {", 8, &settings).is_empty());
+    }
+}
@@ -1,125 +1,185 @@
 use crate::engine_parser::unreal_engine::UnrealClass;
 
-use super::{unreal_engine::Engine, config::*};
+use super::{unreal_engine::{Engine, ENGINE_SCHEMA_VERSION}, manifest::{self, AstManifest}, config::*};
 use std::{path::Path, sync::atomic::{AtomicUsize, Ordering}};
 // static ENGINE_CODE: Lazy<Mutex<Engine>> = Lazy::new(|| Mutex::new(Default::default()));
 const fn split_space(c: char) -> bool{
     c == ' ' || c == '\t'
 }
-static mut RUNTIME_ROOT: String = String::new();
-#[inline]
-pub fn runtime_root() -> String{
-    unsafe{
-        RUNTIME_ROOT.replace("\\", "/")
-    }
+///run all stages, the saved engine model is reused if it is up to date and no header was changed
+pub fn parse(settings: &CustomSettings) -> anyhow::Result<ExportDetails>{
+    let scanned = scan(settings)?;
+    let mut engine = match load_engine(settings)? {
+        Some(saved) if saved.is_up_to_date(settings) && !headers_changed(&scanned, settings) => saved,
+        _ => parse_scanned(scanned, settings)?,
+    };
+    super::bindgen::generate(&mut engine, settings)?;
+    Ok(Default::default())
 }
-///parse classes
-pub fn  parse(settings: CustomSettings) -> anyhow::Result<ExportDetails>{
+///strip engine headers into engine_code
+pub fn scan(settings: &CustomSettings) -> anyhow::Result<Engine>{
     let engine_root = Path::new(&settings.EngineRoot);
-    let runtime_root = engine_root.join("Source/Runtime/");
-    unsafe{ 
-        RUNTIME_ROOT = runtime_root.display().to_string();
-    }
-    let engine_json_path = "configs/engine.json";
-    let engine_code = crate::read_files(&runtime_root.display().to_string(), ".h")?;
-    let engine = if let Ok(engine_json) = std::fs::read_to_string(engine_json_path){
-        serde_json::from_str(&engine_json)?
-    }
-    else{
-        let mut engine = Engine::default();
-        std::fs::create_dir("engine_code").ok();
-        let ignores = settings.IgnoreFiles.clone();
-        let ignore = |file: &str| -> bool{
-            //path in export config
-            if settings.ExportPathRoot.iter().find(|root| file.contains(root.as_str())).is_none(){
-                return true;
-            }
-            let file_name = Path::new(file)
-            .file_name()
-            .expect(&format!("{file} is not a file"))
-            .to_str().unwrap()
-            .split(".")
-            .next()
-            .unwrap()
-            .to_string();
-    
-            for ignore in &ignores {
-                if ignore.starts_with("*") && ignore.ends_with("*"){
-                    if file_name.contains(ignore){
-                        return true;
-                    }
-                }
-                else if ignore.starts_with("*"){
-                    let ignore = &ignore[1..];
-                    if file_name.ends_with(ignore){
-                        return true;
-                    }
+    let runtime_root = engine_root.join("Source").join("Runtime");
+    let code_dir = settings.engine_code_dir();
+    let engine_code = crate::read_files(&runtime_root, ".h")?;
+    let manifest = AstManifest::load(settings);
+    let mut engine = Engine::default();
+    std::fs::create_dir_all(&code_dir).ok();
+    let ignores = settings.IgnoreFiles.clone();
+    let ignore = |file: &str| -> bool{
+        //path in export config
+        if settings.ExportPathRoot.iter().find(|root| file.contains(root.as_str())).is_none(){
+            return true;
+        }
+        let file_name = Path::new(file)
+        .file_name()
+        .unwrap_or_else(|| panic!("{file} is not a file"))
+        .to_str().unwrap()
+        .split(".")
+        .next()
+        .unwrap()
+        .to_string();
+
+        for ignore in &ignores {
+            if ignore.starts_with("*") && ignore.ends_with("*"){
+                if file_name.contains(ignore){
+                    return true;
                 }
-                else if ignore.ends_with("*"){
-                    let ignore = &ignore[0..ignore.len() - 1];
-                    if file_name.starts_with(ignore){
-                        return true;
-                    }
+            }
+            else if let Some(ignore) = ignore.strip_prefix("*") {
+                if file_name.ends_with(ignore){
+                    return true;
                 }
-                else if &file_name == ignore{
+            }
+            else if ignore.ends_with("*"){
+                let ignore = &ignore[0..ignore.len() - 1];
+                if file_name.starts_with(ignore){
                     return true;
                 }
             }
-            false
-        };
-        for file in engine_code{
-            if ignore(&file){
-                continue;
+            else if &file_name == ignore{
+                return true;
             }
-            parse_header(&mut engine, file)?;
         }
-        // println!(
-        //     "class {:?}", 
-        //     engine.classes.first().map(|class| (class.public_apis.last(), class.properties.last()))
-        // );
-        super::ast::run(&mut engine)?;
-        //TODO
-        // let engine_str = serde_json::to_string(&engine)?;
-        // std::fs::write(engine_json_path, engine_str)?;
-        engine
+        false
     };
-    super::bindgen::generate(&engine, &settings)?;
-    Ok(Default::default())
+    for file in engine_code{
+        if ignore(&crate::path_to_string(&file)){
+            continue;
+        }
+        parse_header(&mut engine, &manifest, &runtime_root, &code_dir, &file)?;
+    }
+    // println!(
+    //     "class {:?}", 
+    //     engine.classes.first().map(|class| (class.public_apis.last(), class.properties.last()))
+    // );
+    Ok(engine)
+}
+///true if any scanned header or header included by its dump was changed since the last parse
+fn headers_changed(scanned: &Engine, settings: &CustomSettings) -> bool{
+    let manifest = AstManifest::load(settings);
+    let mut dependency_hashes = Default::default();
+    scanned.source_hashes.len() != manifest.files.len() ||
+    scanned.source_hashes.iter().any(|(relative_path, source_hash)|{
+        manifest.files.get(relative_path).is_none_or(|entry|{
+            &entry.source_hash != source_hash || entry.dependencies_changed(&mut dependency_hashes)
+        })
+    })
+}
+///parse stripped headers with clang and save the engine model
+pub fn parse_engine(settings: &CustomSettings) -> anyhow::Result<Engine>{
+    parse_scanned(scan(settings)?, settings)
+}
+fn parse_scanned(mut engine: Engine, settings: &CustomSettings) -> anyhow::Result<Engine>{
+    //entries of unchanged headers are taken from the saved model
+    let incremental = match load_engine(settings)? {
+        Some(saved) if saved.schema_version == ENGINE_SCHEMA_VERSION => {
+            engine.classes = saved.classes;
+            engine.enums = saved.enums;
+            engine.static_apis = saved.static_apis;
+            true
+        },
+        _ => false,
+    };
+    let mut manifest = super::ast::run(&mut engine, settings, incremental)?;
+    engine.resolve_inheritance();
+    engine.stamp(settings);
+    let engine_json_path = settings.engine_json_path();
+    if let Some(parent) = engine_json_path.parent(){
+        std::fs::create_dir_all(parent).ok();
+    }
+    std::fs::write(&engine_json_path, serde_json::to_string(&engine)?)?;
+    manifest.save(settings)?;
+    Ok(engine)
+}
+///load saved engine model, None if not exists or unreadable
+pub fn load_engine(settings: &CustomSettings) -> anyhow::Result<Option<Engine>>{
+    let engine_json_path = settings.engine_json_path();
+    let Ok(engine_json) = std::fs::read_to_string(&engine_json_path) else{
+        return Ok(None);
+    };
+    match serde_json::from_str(&engine_json) {
+        Ok(engine) => Ok(Some(engine)),
+        Err(e) => {
+            println!("fail to load engine model {} {:?}", engine_json_path.display(), e);
+            Ok(None)
+        }
+    }
+}
+///load saved engine model, parse again if missing or outdated
+pub fn load_or_parse_engine(settings: &CustomSettings) -> anyhow::Result<Engine>{
+    match load_engine(settings)? {
+        Some(engine) if engine.is_up_to_date(settings) => Ok(engine),
+        Some(_) => {
+            //changed headers are found by the manifest
+            println!("engine model {} is outdated, parse again", settings.engine_json_path().display());
+            parse_engine(settings)
+        },
+        None => parse_engine(settings),
+    }
+}
+///generate binders from the saved engine model
+pub fn generate(settings: &CustomSettings) -> anyhow::Result<()>{
+    let mut engine = load_or_parse_engine(settings)?;
+    super::bindgen::generate(&mut engine, settings)
+}
+///remove engine_code, unreal_ast and the parsed engine model
+pub fn clean_cache(settings: &CustomSettings) -> anyhow::Result<()>{
+    for dir in [settings.engine_code_dir(), settings.ast_dir()] {
+        if dir.exists(){
+            std::fs::remove_dir_all(&dir)?;
+        }
+    }
+    let engine_json_path = settings.engine_json_path();
+    if engine_json_path.exists(){
+        std::fs::remove_file(&engine_json_path)?;
+    }
+    Ok(())
 }
 static FAIL_COUNT: AtomicUsize = AtomicUsize::new(0);
 ///parse header struct
-fn parse_header(engine: &mut Engine, file: String) -> anyhow::Result<()>{
-    let file_name = Path::new(&file).file_name().unwrap().to_str().unwrap();
+fn parse_header(engine: &mut Engine, manifest: &AstManifest, runtime_root: &Path, code_dir: &Path, file: &Path) -> anyhow::Result<()>{
+    let file_name = file.file_name().unwrap().to_str().unwrap();
+    let relative_path = file.strip_prefix(runtime_root)?;
+    let source = std::fs::read_to_string(file)?;
+    let source_hash = manifest::hash_content(source.as_bytes());
+    //stripped header is reused only if the source is not changed
+    let unchanged = manifest.source_hash(&crate::path_to_string(relative_path)) == Some(source_hash.as_str());
     // std::fs::write(format!("engine_source/{}", file_name), &content)?;
     // if file_name != "Actor.h" {
     //     return Ok(());
     // }
     // println!("parse file {}", file_name);
-    let cache_path = format!("engine_code/{}", file.replace(&runtime_root(), ""));
+    let cache_path = code_dir.join(relative_path);
     let mut lines;
-    if let Ok(content) = std::fs::read_to_string(&cache_path){
+    if let Some(content) = std::fs::read_to_string(&cache_path).ok().filter(|_| unchanged){
        lines = content.split("\r\n").map(|s| s.to_owned()).collect::<Vec<_>>();
     }
     else 
     {
-        lines = std::fs::read_to_string(&file)?.replace("\r", "").split("\n").map(|s| s.to_owned()).collect::<Vec<_>>();
+        lines = source.replace("\r", "").split("\n").map(|s| s.to_owned()).collect::<Vec<_>>();
         let inner_lines = std::mem::take(&mut lines);
-        match std::panic::catch_unwind(||{
-                    let mut inner_lines = inner_lines;
-                    remove_unreal_tags(&mut inner_lines);
-                    // std::fs::write(format!("engine_code/{}_rem_utag", file_name), lines.join("\r\n"))?;
-                    remove_comment_and_mic(&mut inner_lines);
-                    // // std::fs::write(format!("engine_code/{}_remove_include", file_name), lines.join("\r\n"))?;
-                    remove_defines(&mut inner_lines);
-                    // std::fs::write(format!("engine_code/{}_rem_def", file_name), lines.join("\r\n"))?;
-                    remove_templates(&mut inner_lines);
-                    // std::fs::write(format!("engine_code/{}_rem_temp", file_name), lines.join("\r\n"))?;
-                    remove_deprecated(&mut inner_lines);
-                    // std::fs::write(format!("engine_code/{}_rem_dep", file_name), lines.join("\r\n"))?;
-                    remove_all_fn_block(&mut inner_lines);
-                    //normalize_all(&mut inner_lines);
-                    inner_lines
-                }) {
+        match std::panic::catch_unwind(|| strip_header(inner_lines)) {
             Err(_e) => {
                 // println!("parse flush file {} fail {:?}", file_name, e);
                 FAIL_COUNT.fetch_add(1, Ordering::Release);
@@ -131,7 +191,7 @@ fn parse_header(engine: &mut Engine, file: String) -> anyhow::Result<()>{
         // std::fs::write(format!("engine_code/{}_trip", file_name), lines.join("\r\n"))?;
     }    
     // load_public_exports(&mut lines, engine, &file);
-    if lines.len() == 0{
+    if lines.is_empty(){
         return Ok(());
     }
     let catch_lines = std::mem::take(&mut lines);
@@ -146,13 +206,12 @@ fn parse_header(engine: &mut Engine, file: String) -> anyhow::Result<()>{
             engine.enums.append(&mut cengine.enums);
             engine.static_apis.append(&mut cengine.static_apis);
             engine.value_types.append(&mut cengine.value_types);
-            engine.files.push(file.clone());
+            engine.files.push(crate::path_to_string(Path::new("Source").join("Runtime").join(relative_path)));
             lines = clines;
-            let root = runtime_root();
-            engine.file_paths.insert(file_name.to_string(), file.replace(&root, ""));
-            let file_path = format!("engine_code/{}", file.replace(&root, ""));
-            std::fs::create_dir_all(&Path::new(&file_path).parent().unwrap()).ok();
-            std::fs::write(&file_path, lines.join("\r\n"))?;
+            engine.file_paths.insert(file_name.to_string(), crate::path_to_string(relative_path));
+            engine.source_hashes.insert(crate::path_to_string(relative_path), source_hash);
+            std::fs::create_dir_all(cache_path.parent().unwrap()).ok();
+            std::fs::write(&cache_path, lines.join("\r\n"))?;
             return Ok(());
         }
         Err(_e) => {
@@ -163,6 +222,53 @@ fn parse_header(engine: &mut Engine, file: String) -> anyhow::Result<()>{
     // println!("file {} parse fail", file_name);
     Ok(())
 }
+///strip a header into the code given to clang, uht macros are moved into trailing reflection markers
+pub(super) fn strip_header(mut lines: Vec<String>) -> Vec<String>{
+    let reflections = remove_unreal_tags(&mut lines);
+    // std::fs::write(format!("engine_code/{}_rem_utag", file_name), lines.join("\r\n"))?;
+    remove_comment_and_mic(&mut lines);
+    // // std::fs::write(format!("engine_code/{}_remove_include", file_name), lines.join("\r\n"))?;
+    remove_defines(&mut lines);
+    // std::fs::write(format!("engine_code/{}_rem_def", file_name), lines.join("\r\n"))?;
+    remove_templates(&mut lines);
+    // std::fs::write(format!("engine_code/{}_rem_temp", file_name), lines.join("\r\n"))?;
+    remove_deprecated(&mut lines);
+    // std::fs::write(format!("engine_code/{}_rem_dep", file_name), lines.join("\r\n"))?;
+    remove_all_fn_block(&mut lines);
+    //normalize_all(&mut lines);
+    lines.extend(reflections);
+    lines
+}
+///conditional directives are kept so `Defines` select the branch clang sees,
+///includes are guarded by `__has_include` as only headers found in `IncludePaths` can be included,
+///None if the directive is removed
+fn preprocessor_directive(line: &str) -> Option<Vec<String>>{
+    let directive = line.strip_prefix('#')?.trim_start();
+    let name = directive.split(|c: char| !c.is_ascii_alphanumeric()).next().unwrap_or_default();
+    match name {
+        "if" | "ifdef" | "ifndef" | "elif" | "elifdef" | "elifndef" | "else" | "endif" | "define" => Some(vec![line.to_string()]),
+        "pragma" if directive.split_whitespace().nth(1) == Some("once") => Some(vec![line.to_string()]),
+        "include" => {
+            let header = directive["include".len()..].trim_start();
+            let end = match header.chars().next()? {
+                '"' => header[1..].find('"')?,
+                '<' => header[1..].find('>')?,
+                _ => return None,
+            };
+            let header = &header[..end + 2];
+            //generated headers only exist in the uht output
+            if header.contains(".generated.h"){
+                return None;
+            }
+            Some(vec![
+                format!("#if __has_include({header})"),
+                format!("#include {header}"),
+                "#endif".to_string(),
+            ])
+        },
+        _ => None,
+    }
+}
 ///移除注释
 fn remove_comment_and_mic(lines: &mut Vec<String>){
     enum ReadState{
@@ -182,15 +288,18 @@ fn remove_comment_and_mic(lines: &mut Vec<String>){
     }
     while read_line < lines.len() {
         let line = lines[read_line].trim().to_string();
-        if line.contains("GetLastUpdateVelocity"){
-            println!("pause");
-        }
         match state {
             ReadState::Normal => {
+                if line.starts_with("#"){
+                    let directive = preprocessor_directive(&line).unwrap_or_default();
+                    let count = directive.len();
+                    lines.splice(read_line..=read_line, directive);
+                    read_line += count;
+                    continue;
+                }
                 if line.is_empty() || 
                     line.starts_with("//") || 
                     line.starts_with("using ") ||
-                    (line.starts_with("#") && !line.starts_with("#define")) |
                     type_impl(&line){
                     lines.remove(read_line);
                     continue;
@@ -244,7 +353,7 @@ fn remove_comment_and_mic(lines: &mut Vec<String>){
                 else if let Some(com_end) = line.find("*/"){
                     state = ReadState::Normal;
                     if start_index != usize::MAX{
-                        lines[read_line] = line[0..start_index].to_string() + &line[com_end..].to_string();   
+                        lines[read_line] = line[0..start_index].to_string() + &line[com_end..];   
                     }
                     else{
                         lines[read_line] = line[com_end + 1..].to_string();   
@@ -269,11 +378,15 @@ fn remove_templates(lines: &mut Vec<String>){
     let mut read_line = 0usize;
     'main: while read_line < lines.len() {
         let mut line = lines[read_line].trim().to_owned();
+        if line.starts_with("#"){
+            read_line += 1;
+            continue;
+        }
         if let Some(index) = line.find("template"){
             //remove template <>
             if let Some(brace_end) = line.rfind(">"){
                 line.replace_range(index..=brace_end, "");
-                if line.trim().len() == 0{
+                if line.trim().is_empty(){
                     lines.remove(read_line);
                     continue 'main;
                 }
@@ -284,7 +397,7 @@ fn remove_templates(lines: &mut Vec<String>){
                     line = lines[read_line].trim().to_owned();
                     if let Some(brace_end) = line.find(">"){
                         line.replace_range(0..=brace_end, "");
-                        if line.trim().len() == 0{
+                        if line.trim().is_empty(){
                             lines.remove(read_line);
                         }
                         break;
@@ -342,13 +455,15 @@ fn remove_templates(lines: &mut Vec<String>){
     }
 }
 ///移除标记宏
-fn remove_unreal_tags(lines: &mut Vec<String>){
+///returns reflection markers `//@UPROPERTY AActor::bHidden(BlueprintReadOnly)` of removed uht macros
+fn remove_unreal_tags(lines: &mut Vec<String>) -> Vec<String>{
     let mut read_line = 0usize;
+    let mut reflections = vec![];
+    //UCLASS/USTRUCT being declared and the brace depth out of it
+    let mut owners: Vec<(String, usize)> = vec![];
+    let mut depth = 0usize;
     while read_line < lines.len() {
         let line = lines[read_line].trim().to_owned();
-        if line.contains("GetLastUpdateVelocity"){
-            println!("pause");
-        }
         if line.starts_with("#define"){
             read_line += 1;
             continue;
@@ -359,6 +474,46 @@ fn remove_unreal_tags(lines: &mut Vec<String>){
             read_line += 1;
             continue;
         }
+        if let Some(macro_name) = REFLECTION_MACROS.iter().find(|m| {
+                line.strip_prefix(*m).map(|args| args.trim_start().starts_with('(')).unwrap_or_default()
+            }){
+            let args = take_macro_args(lines, read_line, macro_name);
+            if let Some(name) = reflection_decl_name(lines, read_line, macro_name){
+                let key = match *macro_name {
+                    "UPROPERTY" | "UFUNCTION" => match owners.last() {
+                        Some((owner, _)) => format!("{owner}::{name}"),
+                        None => name,
+                    },
+                    "UENUM" => name,
+                    _ => {
+                        owners.push((name.clone(), depth));
+                        name
+                    },
+                };
+                reflections.push(format!("//@{macro_name} {key}({args})"));
+            }
+            continue;
+        }
+        //dynamic delegate signature, `//@DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam FOnX(AActor*, Actor)`
+        if let Some(macro_name) = dynamic_delegate_macro(&line){
+            let args = take_macro_args(lines, read_line, &macro_name);
+            let (name, params) = args.split_once(',').unwrap_or((&args, ""));
+            if !name.trim().is_empty(){
+                reflections.push(format!("//@{macro_name} {}({})", name.trim(), params.trim()));
+            }
+            continue;
+        }
+        //owner ends at its closing brace
+        for brace in code_braces(&line) {
+            if brace == '{'{
+                depth += 1;
+                continue;
+            }
+            depth = depth.saturating_sub(1);
+            if owners.last().is_some_and(|(_, owner_depth)| *owner_depth == depth){
+                owners.pop();
+            }
+        }
         if line.contains("UENUM") ||
             line.contains("meta") ||
             line.contains("static_assert") ||
@@ -455,11 +610,118 @@ fn remove_unreal_tags(lines: &mut Vec<String>){
         }
         read_line += 1;
     }
+    reflections
+}
+///braces of a line out of string, char literals and line comment
+fn code_braces(line: &str) -> Vec<char>{
+    let mut braces = vec![];
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some(_)) => {
+                chars.next();
+            },
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('/', None) if chars.peek() == Some(&'/') => break,
+            ('{' | '}', None) => braces.push(c),
+            _ => (),
+        }
+    }
+    braces
+}
+const REFLECTION_MACROS: [&str; 5] = ["UCLASS", "USTRUCT", "UENUM", "UPROPERTY", "UFUNCTION"];
+///DECLARE_DYNAMIC_(MULTICAST_)(SPARSE_)DELEGATE_* macro of the line, delegates with return value are not supported
+fn dynamic_delegate_macro(line: &str) -> Option<String>{
+    let (macro_name, _) = line.split_once('(')?;
+    let macro_name = macro_name.trim();
+    (macro_name.starts_with("DECLARE_DYNAMIC_") && macro_name.contains("DELEGATE") && !macro_name.contains("_RetVal") &&
+        macro_name.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    .then(|| macro_name.to_string())
+}
+///remove reflection macro at `read_line`(may span lines) and return its arguments,
+///declaration in the same line is kept
+fn take_macro_args(lines: &mut Vec<String>, read_line: usize, macro_name: &str) -> String{
+    let mut text = lines[read_line].trim()[macro_name.len()..].trim_start().to_string();
+    let mut end_line = read_line;
+    loop {
+        let mut depth = 0;
+        let mut quoted = false;
+        let mut escaped = false;
+        let close = text.char_indices().find(|(_, c)| {
+            if escaped{
+                escaped = false;
+                return false;
+            }
+            match c {
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => {
+                    depth -= 1;
+                    return depth == 0;
+                },
+                _ => (),
+            }
+            false
+        });
+        if let Some((close, _)) = close{
+            let args = text[1..close].trim().to_string();
+            let rest = text[close + 1..].trim().to_string();
+            lines.drain(read_line..end_line);
+            if rest.is_empty(){
+                lines.remove(read_line);
+            }
+            else{
+                lines[read_line] = rest;
+            }
+            return args;
+        }
+        if end_line + 1 >= lines.len(){
+            //unclosed macro
+            lines.drain(read_line..);
+            return String::new();
+        }
+        end_line += 1;
+        text.push(' ');
+        text.push_str(lines[end_line].trim());
+    }
+}
+///name of the declaration follows a reflection macro
+fn reflection_decl_name(lines: &[String], read_line: usize, macro_name: &str) -> Option<String>{
+    fn last_ident(text: &str) -> Option<String>{
+        let text = text.trim_end();
+        let name = &text[text.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_').len()..];
+        (!name.is_empty()).then(|| name.to_string())
+    }
+    let decl = lines.iter().skip(read_line).take(8).map(|line| line.trim()).collect::<Vec<_>>().join(" ");
+    match macro_name {
+        "UPROPERTY" => {
+            let decl = decl.split(';').next()?.split(['=', '{', '[']).next()?.replace("::", "__");
+            //bit field
+            last_ident(decl.split(':').next()?)
+        },
+        "UFUNCTION" => last_ident(decl.split('(').next()?),
+        _ => {
+            //class XXX_API AActor : public UObject
+            decl.split(['{', ';', ':']).next()?
+            .split_whitespace()
+            .filter(|key| !matches!(*key, "class" | "struct" | "enum" | "namespace" | "final") && !key.ends_with("_API"))
+            .rfind(|key| key.chars().all(|c| c.is_alphanumeric() || c == '_'))
+            .map(|key| key.to_string())
+        }
+    }
 }
 fn remove_all_fn_block(lines: &mut Vec<String>){
     let mut read_line = 0usize;
     while read_line < lines.len() {
         let check_line = lines[read_line].trim();
+        //preprocessor directives are kept for clang
+        if check_line.starts_with("#"){
+            read_line += 1;
+            continue;
+        }
         if check_line.starts_with("virtual") && (
             check_line.ends_with("=0;") ||
             check_line.ends_with("= 0;") ||
@@ -530,7 +792,7 @@ fn normalize_function(lines: &mut Vec<String>, read_line: &mut usize) -> bool{
         let mut this_line;
         while valid_check < lines.len() {
             this_line = lines[valid_check].as_str();
-            if let Some(_) = this_line.find(")"){
+            if this_line.find(")").is_some(){
                 if start_brace.is_some(){
                     brack_end_line = valid_check;
                     break 'root;
@@ -541,7 +803,7 @@ fn normalize_function(lines: &mut Vec<String>, read_line: &mut usize) -> bool{
                 }
             }
             //end with no )
-            else if let Some(_) = this_line.find(";"){
+            else if this_line.find(";").is_some(){
                 //not function
                 return false;
             }
@@ -698,7 +960,7 @@ fn load_public_exports(lines: &mut Vec<String>, engine: &mut Engine, file_path:
                                     let start_line = read_line;
                                     let (member,end) = parse_class_member(lines, &mut read_line, Some(&class_info.name));
                                     if member.is_some(){
-                                        (start_line..read_line+1).into_iter().for_each(|idx| 
+                                        (start_line..read_line+1).for_each(|idx| 
                                             if idx < lines.len(){
                                                 lines.remove(idx);
                                             }
@@ -763,7 +1025,7 @@ fn load_public_exports(lines: &mut Vec<String>, engine: &mut Engine, file_path:
 enum ClassMember {
     Property(CppProperty),
     Function(CppApi),
-    Brace(BraceType),
+    Brace(#[allow(unused)] BraceType),
     #[allow(unused)]
     Empty,
 }
@@ -789,10 +1051,10 @@ fn parse_class_member(lines: &mut Vec<String>, read_line: &mut usize, class_name
     match parse_remove_brace(lines, read_line, true) {
         Some(brace) => {
             match brace {
-                BraceType::Start => return (Some(ClassMember::Brace(brace)), false),
-                BraceType::End => return (Some(ClassMember::Brace(brace)), true),
+                BraceType::Start => (Some(ClassMember::Brace(brace)), false),
+                BraceType::End => (Some(ClassMember::Brace(brace)), true),
                 BraceType::StartAndEnd(_) => {
-                    return (Some(ClassMember::Brace(brace)), true);
+                    (Some(ClassMember::Brace(brace)), true)
                 },
             }
         },
@@ -836,7 +1098,7 @@ fn remove_fn_block(lines: &mut Vec<String>, read_line: &mut usize) -> Option<Str
             if line.ends_with(";"){
                 return None;
             }
-            lines[*read_line].push_str(";");
+            lines[*read_line].push(';');
             *read_line += 1;
             while *read_line < lines.len() {
                 let line_content = lines.remove(*read_line);
@@ -905,7 +1167,7 @@ fn parse_remove_brace(lines: &mut Vec<String>, read_line: &mut usize, remove: bo
             if remove{
                 lines[*read_line] = line[0..start].to_string();
                 if end < line.len(){
-                    lines[*read_line].push_str(&line[end+1..].to_string());
+                    lines[*read_line].push_str(&line[end+1..]);
                 }
             }
             Some(BraceType::StartAndEnd(line[start..end].to_string()))
@@ -913,7 +1175,7 @@ fn parse_remove_brace(lines: &mut Vec<String>, read_line: &mut usize, remove: bo
         (None, Some(end)) => {
             if !line.ends_with("};") &&
                 end != line.len() - 1{
-                println!("end content maybe error ignored in the end of {{{}", line[end..].to_string());
+                println!("end content maybe error ignored in the end of {{{}", &line[end..]);
             }
             if remove{
                 lines[*read_line] = line[0..end].to_string();
@@ -928,7 +1190,7 @@ fn parse_remove_brace(lines: &mut Vec<String>, read_line: &mut usize, remove: bo
     ret
 }
 #[allow(unused)]
-fn parse_brace_line(lines: &mut Vec<String>, read_line: &mut usize) -> Option<BraceType>{
+fn parse_brace_line(lines: &mut [String], read_line: &mut usize) -> Option<BraceType>{
     let line = lines[*read_line].trim();
     if line == "}"{
         Some(BraceType::End)
@@ -944,7 +1206,7 @@ fn parse_brace_line(lines: &mut Vec<String>, read_line: &mut usize) -> Option<Br
     }
 }
 ///解析函数
-fn parse_function(lines: &mut Vec<String>, read_line: &mut usize, class_name: Option<&String>) -> Option<CppApi>{    
+fn parse_function(lines: &mut [String], read_line: &mut usize, class_name: Option<&String>) -> Option<CppApi>{    
     let mut line = lines[*read_line].trim().to_string();
     let start = line.find("(");
     let end = line.rfind(")");
@@ -1008,10 +1270,12 @@ fn parse_function(lines: &mut Vec<String>, read_line: &mut usize, class_name: Op
             .filter(|s| !s.is_empty())
             .map(|sa| sa.to_string())
             .collect::<Vec<_>>();
-            let mut c_fn = CppApi::default();
-            c_fn.is_override = override_flag;
-            c_fn.class_name = class_name.cloned().unwrap_or_default();
-            c_fn.parameters = super::parse_parameter(&parameters);
+            let mut c_fn = CppApi{
+                is_override: override_flag,
+                class_name: class_name.cloned().unwrap_or_default(),
+                parameters: super::parse_parameter(&parameters),
+                ..Default::default()
+            };
             super::parse_function(&mut c_fn, &function_impl);
             Some(c_fn)
         }
@@ -1019,7 +1283,7 @@ fn parse_function(lines: &mut Vec<String>, read_line: &mut usize, class_name: Op
     }
 }
 #[allow(unused)]
-fn normalize_all(lines: &mut Vec<String>){
+fn normalize_all(lines: &mut [String]){
     let mut read_line = 0;
     while read_line < lines.len() {
         normalize_line(&mut lines[read_line]);
@@ -1041,26 +1305,22 @@ fn normalize_line(line: &mut String){
     while index > 1 {
         let c = line[index..=index].chars().next().unwrap();
         match c{
-            ' ' | '\t' => {
-                if sharp_bracket{
-                    //ignore <const Type>
-                    if index > 5{
-                        let const_expr = &line[..index];
-                        if !const_expr.ends_with("const") &&
-                        !const_expr.ends_with("const&"){
-                            line.remove(index);
-                        }
-                    }
-                    else{
+            ' ' | '\t' if sharp_bracket => {
+                //ignore <const Type>
+                if index > 5{
+                    let const_expr = &line[..index];
+                    if !const_expr.ends_with("const") &&
+                    !const_expr.ends_with("const&"){
                         line.remove(index);
                     }
                 }
+                else{
+                    line.remove(index);
+                }
             },
             //space after ptr 
-            '*' if !sharp_bracket && index + 1 < len => {
-                if !is_space(&line[index+1..=index+1]){
-                    line.insert(index + 1, ' ');
-                }
+            '*' if !sharp_bracket && index + 1 < len && !is_space(&line[index+1..=index+1]) => {
+                line.insert(index + 1, ' ');
             },
             //no space before >
             '>' => {
@@ -1078,20 +1338,16 @@ fn normalize_line(line: &mut String){
                 sharp_bracket = false;
             }
             //space after &(&&)
-            '&' if index + 1 < len => {
-                //if next char is not &, then this is ref type
-                if &line[index + 1..=index + 1] != "&"{
-                    if !is_space(&line[index + 1..=index + 1]){
-                        line.insert(index + 1, ' ');
-                    }
-                }
+            //if next char is not &, then this is ref type
+            '&' if index + 1 < len && &line[index + 1..=index + 1] != "&" && !is_space(&line[index + 1..=index + 1]) => {
+                line.insert(index + 1, ' ');
             }
             _ => ()
         }
         index -= 1;
     }
 }
-fn parse_field(lines: &mut Vec<String>, read_line: &mut usize) -> Option<CppProperty>{
+fn parse_field(lines: &mut [String], read_line: &mut usize) -> Option<CppProperty>{
     let mut line = lines[*read_line].trim().to_string();
     if line.contains(";") && !line.contains("}"){
         let mut property = CppProperty::default();
@@ -1121,10 +1377,10 @@ fn parse_field(lines: &mut Vec<String>, read_line: &mut usize) -> Option<CppProp
             let left = line[index - 1..index].to_string();
             let right = line[index + 1..line.len().min(index + 2)].to_string();
             if right != " " && right != "\t"{
-                line.insert_str(index + 1, " ");
+                line.insert(index + 1, ' ');
             }
             if left != " " && left != "\t"{
-                line.insert_str(index - 1, " ");
+                line.insert(index - 1, ' ');
             }
         }
         let property_info = line
@@ -1153,18 +1409,18 @@ fn parse_field(lines: &mut Vec<String>, read_line: &mut usize) -> Option<CppProp
             if count > 3{
                 iter.next();
                 iter.next();
-                property.name = iter.next().expect(&format!("unknow property {}", line));
+                property.name = iter.next().unwrap_or_else(|| panic!("unknow property {}", line));
             }
             // name:value
             else{
-                let name_slice = iter.next().expect(&format!("unknow property {}", line));
-                property.name = name_slice.split(":").next().expect(&format!("unknow property {}", line)).to_string();
+                let name_slice = iter.next().unwrap_or_else(|| panic!("unknow property {}", line));
+                property.name = name_slice.split(":").next().unwrap_or_else(|| panic!("unknow property {}", line)).to_string();
             }
         }
         else{
-            property.name = iter.next().expect(&format!("unknow property {}", line));
+            property.name = iter.next().unwrap_or_else(|| panic!("unknow property {}", line));
         }
-        property.type_str = iter.next().expect(&format!("unknow property {}", line));
+        property.type_str = iter.next().unwrap_or_else(|| panic!("unknow property {}", line));
         if let Some(index) = property.type_str.find("*"){
             property.type_str = property.type_str[0..index].to_string();
             property.is_ptr = true;
@@ -1,6 +1,8 @@
 #![allow(non_snake_case)]
 #![allow(non_upper_case_globals)]
 
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
+
 use serde::{Deserialize, Serialize};
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +62,148 @@ impl PartialEq<ValueType> for i32{
         ValueType::from(*self) == *other
     }
 }
+///unreal reflection specifiers of UCLASS/USTRUCT/UENUM/UPROPERTY/UFUNCTION
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Specifiers{
+    ///specifiers without value, e.g. BlueprintCallable
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub flags: Vec<String>,
+    ///specifiers with value, e.g. Category="Actor"
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub values: BTreeMap<String, String>,
+    ///meta=(...)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub meta: BTreeMap<String, String>,
+}
+impl Specifiers{
+    pub fn is_empty(&self) -> bool{
+        self.flags.is_empty() && self.values.is_empty() && self.meta.is_empty()
+    }
+    ///specifiers are case insensitive in uht
+    pub fn has(&self, flag: &str) -> bool{
+        self.flags.iter().any(|f| f.eq_ignore_ascii_case(flag))
+    }
+    pub fn value(&self, key: &str) -> Option<&str>{
+        self.values.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v.as_str())
+    }
+    ///parse macro arguments, e.g. `BlueprintCallable, Category="Actor", meta=(DisplayName="Foo")`
+    pub fn parse(text: &str) -> Self{
+        let mut specifiers = Self::default();
+        for item in split_specifiers(text){
+            match item.split_once('=') {
+                Some((key, value)) if key.trim().eq_ignore_ascii_case("meta") => {
+                    let value = value.trim();
+                    let value = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')).unwrap_or(value);
+                    for meta in split_specifiers(value){
+                        let (key, value) = meta.split_once('=').unwrap_or((&meta, ""));
+                        specifiers.meta.insert(key.trim().to_string(), unquote(value));
+                    }
+                },
+                Some((key, value)) => {
+                    specifiers.values.insert(key.trim().to_string(), unquote(value));
+                },
+                None => specifiers.flags.push(item),
+            }
+        }
+        specifiers
+    }
+}
+///split by commas out of brackets and quotes, `\"` does not end a quote
+fn split_specifiers(text: &str) -> Vec<String>{
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut item = String::new();
+    for c in text.chars() {
+        if escaped{
+            escaped = false;
+            item.push(c);
+            continue;
+        }
+        match c {
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                items.push(std::mem::take(&mut item));
+                continue;
+            },
+            _ => (),
+        }
+        item.push(c);
+    }
+    items.push(item);
+    items.into_iter().map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect()
+}
+///strip quotes of a string literal and unescape it
+fn unquote(value: &str) -> String{
+    let value = value.trim();
+    let Some(value) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else{
+        return value.to_string();
+    };
+    let mut unquoted = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContainerKind{
+    Array,
+    Set,
+    Map,
+}
+///engine container type with its element types, e.g. `TMap<FName, int32>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Container{
+    pub kind: ContainerKind,
+    ///element type of TArray/TSet, key and value type of TMap
+    pub elements: Vec<String>,
+}
+impl Container{
+    ///parse TArray/TSet/TMap, None if it has custom allocator, key funcs or is not a container
+    pub fn parse(type_str: &str) -> Option<Self>{
+        let (kind, args) = [("TArray<", ContainerKind::Array), ("TSet<", ContainerKind::Set), ("TMap<", ContainerKind::Map)]
+        .into_iter()
+        .find_map(|(prefix, kind)| type_str.trim().strip_prefix(prefix).map(|args| (kind, args)))?;
+        let args = args.strip_suffix('>')?;
+        let elements = split_type_args(args).into_iter().map(Self::element).collect::<Vec<_>>();
+        let count = if kind == ContainerKind::Map{ 2 } else { 1 };
+        if elements.len() != count{
+            return None;
+        }
+        Some(Self{ kind, elements })
+    }
+    ///`const class AActor *` -> `AActor*`
+    fn element(type_str: &str) -> String{
+        type_str.replace("const ", "").replace("class ", "").replace("struct ", "").trim().replace(" *", "*")
+    }
+}
+///split type arguments by commas out of angle brackets
+fn split_type_args(args: &str) -> Vec<&str>{
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&args[start..index]);
+                start = index + 1;
+            },
+            _ => (),
+        }
+    }
+    items.push(&args[start..]);
+    items
+}
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Parameter{
     #[serde(skip_serializing_if = "super::is_false", default)]
@@ -72,6 +216,9 @@ pub struct Parameter{
     pub ptr_param: bool,
     #[serde(skip_serializing_if = "super::is_false", default)]
     pub is_generic: bool,
+    ///TArray/TSet/TMap parameter
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub container: Option<Container>,
     pub param_type: i32,
     pub name: String,
     ///c type name
@@ -80,6 +227,41 @@ pub struct Parameter{
     pub r_type: String,
     pub default_value: Option<String>,
 }
+impl Parameter{
+    ///parameter of macro declaration, e.g. `const FHitResult&` and `SweepResult`
+    pub fn from_decl(type_str: &str, name: &str) -> Self{
+        let mut param = Self{
+            name: name.trim().to_string(),
+            ..Default::default()
+        };
+        let mut type_str = type_str.replace("class ", "").replace("struct ", "").trim().to_string();
+        if let Some(rest) = type_str.strip_prefix("const "){
+            type_str = rest.trim().to_string();
+            param.const_param = true;
+        }
+        if let Some(rest) = type_str.strip_suffix("&&"){
+            type_str = rest.trim().to_string();
+            param.move_param = true;
+        }
+        else if let Some(rest) = type_str.strip_suffix('&'){
+            type_str = rest.trim().to_string();
+            param.ref_param = true;
+        }
+        else if let Some(rest) = type_str.strip_suffix('*'){
+            type_str = rest.trim().to_string();
+            param.ptr_param = true;
+        }
+        if type_str.contains('<') && type_str.contains('>'){
+            param.is_generic = true;
+            param.container = Container::parse(&type_str);
+        }
+        let (r_type, vt) = super::parse_c_type(&type_str);
+        param.r_type = r_type;
+        param.param_type = vt as i32;
+        param.type_str = type_str;
+        param
+    }
+}
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CppApi{    
     pub return_type: i32,
@@ -114,7 +296,58 @@ pub struct CppApi{
     pub rc_type: String,
     ///result rust type name
     pub r_type: String,
+    ///TArray/TSet/TMap result
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ret_container: Option<Container>,
     pub function_block: Option<String>,
+    ///UFUNCTION specifiers
+    #[serde(skip_serializing_if = "Specifiers::is_empty", default)]
+    pub specifiers: Specifiers,
+    ///header of a global api
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub path: String,
+}
+///dynamic delegate declared by `DECLARE_DYNAMIC_MULTICAST_DELEGATE_TwoParams(FOnX, AActor*, Actor, bool, bFlag)`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CppDelegate{
+    pub name: String,
+    #[serde(skip_serializing_if = "super::is_false", default)]
+    pub multicast: bool,
+    ///declared with owner class and property name, e.g. `OnActorBeginOverlap`
+    #[serde(skip_serializing_if = "super::is_false", default)]
+    pub sparse: bool,
+    pub parameters: Vec<Parameter>,
+    ///header the delegate is declared in
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub path: String,
+}
+impl CppDelegate{
+    ///parse declare macro arguments after the delegate name, None if the type and name of a parameter are not paired
+    ///or the delegate has return value(`_RetVal`, the return type is the first macro argument)
+    pub fn parse(macro_name: &str, name: &str, args: &str) -> Option<Self>{
+        if macro_name.contains("_RetVal"){
+            return None;
+        }
+        let sparse = macro_name.contains("_SPARSE_");
+        let mut args = split_type_args(args).into_iter().map(str::trim).filter(|arg| !arg.is_empty()).collect::<Vec<_>>();
+        //owner class and property name
+        if sparse{
+            if args.len() < 2{
+                return None;
+            }
+            args.drain(..2);
+        }
+        if args.len() % 2 != 0{
+            return None;
+        }
+        Some(Self{
+            name: name.to_string(),
+            multicast: macro_name.contains("_MULTICAST_"),
+            sparse,
+            parameters: args.chunks(2).map(|pair| Parameter::from_decl(pair[0], pair[1])).collect(),
+            path: String::new(),
+        })
+    }
 }
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Parameters{
@@ -131,6 +364,12 @@ pub struct CppEnum{
     pub namespace_enum: bool,
     pub name: String,
     pub constants: Vec<CppEnumConstant>,
+    ///UENUM specifiers
+    #[serde(skip_serializing_if = "Specifiers::is_empty", default)]
+    pub specifiers: Specifiers,
+    ///header the enum is declared in
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub path: String,
 }
 impl CppEnum{
     pub fn equal(&self, param_name: &str) -> bool{
@@ -161,15 +400,33 @@ pub struct CppProperty{
     ///bit value supporte only for opaque
     #[serde(skip_serializing_if = "super::is_false", default)]
     pub bit_value: bool,
+    ///width of bit value, 0 if it is not a literal
+    #[serde(skip_serializing_if = "super::is_zero", default)]
+    pub bit_width: usize,
+    ///length of static array `T Field[N]`(type_str is T), 0 if it is not an array
+    #[serde(skip_serializing_if = "super::is_zero", default)]
+    pub array_dim: usize,
+    ///anonymous union or struct member(type_str is the generated record), its members are accessed through the owner in c++
+    #[serde(skip_serializing_if = "super::is_false", default)]
+    pub anonymous: bool,
     ///not supported yet
     #[serde(skip_serializing_if = "super::is_false", default)]
     pub unsupported: bool,
+    ///TArray/TSet/TMap field
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub container: Option<Container>,
     pub name: String,
     ///c type name
     pub type_str: String,
     ///rust type name
     pub r_type: String,    
     pub value: Option<String>,
+    ///byte offset reported by clang record layout
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub offset: Option<usize>,
+    ///UPROPERTY specifiers
+    #[serde(skip_serializing_if = "Specifiers::is_empty", default)]
+    pub specifiers: Specifiers,
 }
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExportDetails{
@@ -193,6 +450,53 @@ pub struct ExportClassSetting{
     ///functions whill not export, if functions field was set,this field will not take affect
     #[serde(default)]
     pub ignore_functions: Vec<String>,
+    ///export BlueprintCallable/BlueprintPure functions and BlueprintReadWrite/BlueprintReadOnly fields only,
+    ///global BlueprintOnly if not set, functions/fields white list is not affected
+    #[serde(default)]
+    pub blueprint_only: Option<bool>,
+    ///virtual functions forwarded to rust by generated uclass `Rust` subclass, e.g. `ARustActor` of `AActor`
+    #[serde(default)]
+    pub overrides: Vec<String>,
+}
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TypeWrapper{
+    ///unreal type and the wrapped type, both the rust type and converters are defined by user
+    Alias([String; 2]),
+    ///rust type and To{rs_type}/To{cpp_type} converters are generated
+    Mapped(MappedWrapper),
+}
+impl TypeWrapper{
+    pub fn cpp_type(&self) -> &str{
+        match self {
+            TypeWrapper::Alias([cpp_type, _]) => cpp_type,
+            TypeWrapper::Mapped(mapped) => &mapped.cpp_type,
+        }
+    }
+    pub fn rs_type(&self) -> &str{
+        match self {
+            TypeWrapper::Alias([_, rs_type]) => rs_type,
+            TypeWrapper::Mapped(mapped) => &mapped.rs_type,
+        }
+    }
+}
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MappedWrapper{
+    pub cpp_type: String,
+    pub rs_type: String,
+    ///fields of the rust type in order
+    pub fields: Vec<WrapperField>,
+    ///derives besides Default, Clone, Copy, Debug and PartialEq, e.g. `["Eq", "Hash"]`
+    #[serde(default)]
+    pub derives: Vec<String>,
+}
+///`{"name": "x", "cpp_field": "X", "rs_type": "f32"}`, numbers are casted(e.g. double to f32),
+///fields of other wrapper types are converted by their converters
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WrapperField{
+    pub name: String,
+    pub cpp_field: String,
+    pub rs_type: String,
 }
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct CustomSettings{
@@ -203,12 +507,203 @@ pub struct CustomSettings{
     pub ForceOpaque: Vec<String>,
     ///黑名单类型(接口)
     pub BlackList: Vec<String>,
+    #[allow(unused)]
     pub ExportApis: Vec<String>,
     pub ExportEnums: Vec<String>,
     ///忽略文件列表
     pub IgnoreFiles: Vec<String>,
     ///支持的导出目录
     pub ExportPathRoot: Vec<String>,
-    ///cpp style type wrapper, key is unreal type, value is the wrapped type(witch should be defined by user)
-    pub TypeWrapper: Vec<[String;2]>,
-}
\ No newline at end of file
+    ///cpp style type wrapper, `["FColor", "Color"]` is defined by user(wrapper.rs and ToColor/ToFColor),
+    ///or generated from field mapping `{"cpp_type": "FIntPoint", "rs_type": "IntPoint", "fields": [..]}`
+    pub TypeWrapper: Vec<TypeWrapper>,
+    ///export blueprint visible functions and fields only(BlueprintReadOnly fields have getters only)
+    #[serde(default)]
+    pub BlueprintOnly: bool,
+    ///clang executable, `clang` in system path if empty
+    #[serde(default)]
+    pub ClangPath: String,
+    ///clang include paths(-I), relative path is based on EngineRoot
+    #[serde(default)]
+    pub IncludePaths: Vec<String>,
+    ///clang defines(-D), e.g. `PLATFORM_LITTLE_ENDIAN=1`
+    #[serde(default)]
+    pub Defines: Vec<String>,
+    ///extra arguments appended to the clang ast dump command
+    #[serde(default)]
+    pub ClangArgs: Vec<String>,
+    ///root of engine_code/, unreal_ast/ and engine.json, current directory if empty
+    #[serde(default)]
+    pub CacheDir: String,
+    ///binder output directory(cpp/ and rs/ will be created inside), `Binders` if empty
+    #[serde(default)]
+    pub OutputDir: String,
+    ///clang worker threads, cpu count if 0
+    #[serde(default)]
+    pub ThreadCount: usize,
+    ///generated methods return Result<T, BindingError> instead of panic if native function is not registered
+    #[serde(default)]
+    pub CheckedBindings: bool,
+    ///dump record layouts(size, align and field offsets) with clang(>= 16) to decide transparent structs
+    #[serde(default)]
+    pub DumpRecordLayouts: bool,
+    ///math wrappers(Vector3, Rotator, Quaternion) use f64 and glam DVec3/DQuat as ue5 FVector/FRotator/FQuat, f32 if not set
+    #[serde(default)]
+    pub LargeWorldCoordinates: bool,
+}
+impl CustomSettings{
+    ///rust type of `Real` in wrapper.rs
+    pub fn real_type(&self) -> &'static str{
+        if self.LargeWorldCoordinates{ "f64" } else { "f32" }
+    }
+    ///c++ type of `Real` in Binder.h
+    pub fn cpp_real_type(&self) -> &'static str{
+        if self.LargeWorldCoordinates{ "double" } else { "float" }
+    }
+    pub fn blueprint_only(&self, class: &ExportClassSetting) -> bool{
+        class.blueprint_only.unwrap_or(self.BlueprintOnly)
+    }
+    pub fn clang_path(&self) -> &str{
+        if self.ClangPath.is_empty(){ "clang" } else { self.ClangPath.as_str() }
+    }
+    pub fn cache_dir(&self) -> PathBuf{
+        PathBuf::from(&self.CacheDir)
+    }
+    ///stripped engine headers
+    pub fn engine_code_dir(&self) -> PathBuf{
+        self.cache_dir().join("engine_code")
+    }
+    ///raw clang ast dumps
+    pub fn ast_dir(&self) -> PathBuf{
+        self.cache_dir().join("unreal_ast")
+    }
+    ///parsed engine model
+    pub fn engine_json_path(&self) -> PathBuf{
+        self.cache_dir().join("engine.json")
+    }
+    pub fn output_dir(&self) -> PathBuf{
+        if self.OutputDir.is_empty(){ PathBuf::from("Binders") } else { PathBuf::from(&self.OutputDir) }
+    }
+    ///include paths resolved against EngineRoot
+    pub fn include_paths(&self) -> Vec<PathBuf>{
+        self.IncludePaths.iter().map(|path| Path::new(&self.EngineRoot).join(path)).collect()
+    }
+    pub fn thread_count(&self) -> usize{
+        if self.ThreadCount == 0{ num_cpus::get() } else { self.ThreadCount }
+    }
+    ///hash of everything the parsed engine model depends on,
+    ///export settings are not included as they only affect generation
+    pub fn input_hash(&self) -> String{
+        let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+        let mut update = |s: &str|{
+            hasher.update(s.as_bytes());
+            hasher.update(&[0]);
+        };
+        update(&self.EngineRoot);
+        //engine upgrade in place
+        let build_version = Path::new(&self.EngineRoot).join("Build").join("Build.version");
+        update(&std::fs::read_to_string(build_version).unwrap_or_default());
+        self.ExportPathRoot.iter().for_each(|s| update(s));
+        update("");
+        self.IgnoreFiles.iter().for_each(|s| update(s));
+        update("");
+        update(self.clang_path());
+        self.IncludePaths.iter().for_each(|s| update(s));
+        update("");
+        self.Defines.iter().for_each(|s| update(s));
+        update("");
+        self.ClangArgs.iter().for_each(|s| update(s));
+        if self.DumpRecordLayouts{
+            update("-fdump-record-layouts-complete");
+        }
+        format!("{:016x}", hasher.digest())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn specifiers_flags_values_and_meta() {
+        let specifiers = Specifiers::parse(r#"BlueprintCallable, Category="Actor|Tags", meta=(DisplayName="Get Tags", AdvancedDisplay)"#);
+        assert_eq!(specifiers.flags, vec!["BlueprintCallable"]);
+        assert!(specifiers.has("blueprintcallable"));
+        assert_eq!(specifiers.value("category"), Some("Actor|Tags"));
+        assert_eq!(specifiers.meta.get("DisplayName").map(String::as_str), Some("Get Tags"));
+        assert_eq!(specifiers.meta.get("AdvancedDisplay").map(String::as_str), Some(""));
+        assert!(Specifiers::parse("").is_empty());
+    }
+    #[test]
+    fn specifiers_escaped_quotes() {
+        let specifiers = Specifiers::parse(r#"EditAnywhere, meta=(ToolTip="a \"b\", c", ClampMin="0"), Category="(x, y)""#);
+        assert_eq!(specifiers.flags, vec!["EditAnywhere"]);
+        assert_eq!(specifiers.meta.get("ToolTip").map(String::as_str), Some(r#"a "b", c"#));
+        assert_eq!(specifiers.meta.get("ClampMin").map(String::as_str), Some("0"));
+        assert_eq!(specifiers.value("Category"), Some("(x, y)"));
+        let specifiers = Specifiers::parse(r#"meta=(ToolTip="C:\\Path\\", Keywords="=")"#);
+        assert_eq!(specifiers.meta.get("ToolTip").map(String::as_str), Some(r"C:\Path\"));
+        assert_eq!(specifiers.meta.get("Keywords").map(String::as_str), Some("="));
+    }
+    #[test]
+    fn container_elements() {
+        let array = Container::parse("TArray<const class AActor *>").unwrap();
+        assert_eq!(array.kind, ContainerKind::Array);
+        assert_eq!(array.elements, vec!["AActor*"]);
+        let set = Container::parse("TSet<FName>").unwrap();
+        assert_eq!(set.kind, ContainerKind::Set);
+        assert_eq!(set.elements, vec!["FName"]);
+        assert_eq!(Container::parse("FString"), None);
+    }
+    #[test]
+    fn container_nested_templates() {
+        let array = Container::parse("TArray<TArray<int32> >").unwrap();
+        assert_eq!(array.elements, vec!["TArray<int32>"]);
+        let map = Container::parse("TMap<FName, TArray<FVector>>").unwrap();
+        assert_eq!(map.kind, ContainerKind::Map);
+        assert_eq!(map.elements, vec!["FName", "TArray<FVector>"]);
+        let map = Container::parse("TMap<TPair<int32, int32>, TMap<FName, float>>").unwrap();
+        assert_eq!(map.elements, vec!["TPair<int32, int32>", "TMap<FName, float>"]);
+    }
+    #[test]
+    fn container_custom_allocator() {
+        assert_eq!(Container::parse("TArray<int32, TInlineAllocator<4>>"), None);
+        assert_eq!(Container::parse("TMap<FName, int32, FDefaultSetAllocator, FKeyFuncs>"), None);
+        assert_eq!(Container::parse("TMap<FName>"), None);
+    }
+    #[test]
+    fn parameter_from_decl() {
+        let param = Parameter::from_decl("const FHitResult&", " SweepResult");
+        assert_eq!((param.type_str.as_str(), param.name.as_str()), ("FHitResult", "SweepResult"));
+        assert!(param.const_param && param.ref_param && !param.ptr_param);
+        let param = Parameter::from_decl("class AActor*", "OtherActor");
+        assert_eq!(param.type_str, "AActor");
+        assert!(param.ptr_param && !param.const_param && !param.ref_param);
+        let param = Parameter::from_decl("FVector&", "Out");
+        assert!(param.ref_param && !param.const_param);
+        let param = Parameter::from_decl("const TArray<int32>&", "Values");
+        assert!(param.is_generic && param.const_param && param.ref_param);
+        assert_eq!(param.container.map(|c| c.elements), Some(vec!["int32".to_string()]));
+        let param = Parameter::from_decl("float", "Delta");
+        assert_eq!((param.type_str.as_str(), param.r_type.as_str()), ("float", "f32"));
+    }
+    #[test]
+    fn delegate_params_and_kinds() {
+        let delegate = CppDelegate::parse("DECLARE_DYNAMIC_MULTICAST_DELEGATE_TwoParams", "FOnHit", "class AActor*, Actor, const FHitResult&, Hit").unwrap();
+        assert!(delegate.multicast && !delegate.sparse);
+        assert_eq!(delegate.parameters.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["Actor", "Hit"]);
+        assert!(delegate.parameters[0].ptr_param);
+        assert!(delegate.parameters[1].const_param && delegate.parameters[1].ref_param);
+        //owner class and property name are skipped
+        let delegate = CppDelegate::parse("DECLARE_DYNAMIC_MULTICAST_SPARSE_DELEGATE_OneParam", "FOnPinged", "AFoo, OnPinged, int32, Count").unwrap();
+        assert!(delegate.multicast && delegate.sparse);
+        assert_eq!(delegate.parameters.len(), 1);
+        assert_eq!(delegate.parameters[0].type_str, "int32");
+        let delegate = CppDelegate::parse("DECLARE_DYNAMIC_DELEGATE", "FOnSingle", "").unwrap();
+        assert!(!delegate.multicast && delegate.parameters.is_empty());
+        //template arguments are not split
+        let delegate = CppDelegate::parse("DECLARE_DYNAMIC_DELEGATE_OneParam", "FOnCounts", "const TMap<FName, int32>&, Counts").unwrap();
+        assert_eq!(delegate.parameters[0].type_str, "TMap<FName, int32>");
+        //unpaired parameter and return value
+        assert!(CppDelegate::parse("DECLARE_DYNAMIC_DELEGATE_OneParam", "FOnBad", "int32").is_none());
+        assert!(CppDelegate::parse("DECLARE_DYNAMIC_DELEGATE_RetVal_OneParam", "FOnRet", "bool, int32, Value").is_none());
+    }
+}
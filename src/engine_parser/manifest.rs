@@ -0,0 +1,104 @@
+use std::{collections::BTreeMap, path::PathBuf};
+use serde::{Serialize, Deserialize};
+
+use super::{config::CustomSettings, unreal_engine::ENGINE_SCHEMA_VERSION};
+///what an ast dump of one header was made from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry{
+    ///hash of the original engine header
+    pub source_hash: String,
+    ///hash of the stripped header in engine_code
+    pub preprocessed_hash: String,
+    pub clang_version: String,
+    ///full clang argument list except the file
+    pub flags: Vec<String>,
+    ///headers included by the dump and their hashes, from the clang depfile
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+}
+impl ManifestEntry{
+    ///true if any included header was changed or removed since the dump,
+    ///`hashes` caches the current hash of headers shared by entries
+    pub fn dependencies_changed(&self, hashes: &mut BTreeMap<String, Option<String>>) -> bool{
+        self.dependencies.iter().any(|(path, hash)|{
+            let current = hashes.entry(path.clone())
+            .or_insert_with(|| std::fs::read(path).ok().map(|content| hash_content(&content)));
+            current.as_deref() != Some(hash.as_str())
+        })
+    }
+}
+///per header manifest of unreal_ast, key is the path relative to Source/Runtime
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AstManifest{
+    ///stripped headers and dumps of other schema versions are not reused
+    #[serde(default)]
+    pub schema_version: u32,
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+impl AstManifest{
+    fn path(settings: &CustomSettings) -> PathBuf{
+        settings.ast_dir().join("manifest.json")
+    }
+    ///load saved manifest, empty if not exists or unreadable
+    pub fn load(settings: &CustomSettings) -> Self{
+        std::fs::read_to_string(Self::path(settings))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+        .filter(|manifest| manifest.schema_version == ENGINE_SCHEMA_VERSION)
+        .unwrap_or_default()
+    }
+    pub fn save(&mut self, settings: &CustomSettings) -> anyhow::Result<()>{
+        self.schema_version = ENGINE_SCHEMA_VERSION;
+        let path = Self::path(settings);
+        if let Some(parent) = path.parent(){
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+    ///source hash of the header when it was stripped last time
+    pub fn source_hash(&self, relative_path: &str) -> Option<&str>{
+        self.files.get(relative_path).map(|entry| entry.source_hash.as_str())
+    }
+}
+pub fn hash_content(content: &[u8]) -> String{
+    format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content))
+}
+///headers of a clang depfile (`-MD -MF`) except the dumped header itself
+///```text
+///Foo.o: /engine_code/Foo.h \
+///  /engine_code/Core/Bar.h /engine_code/My\ Dir/Baz.h
+///```
+pub fn parse_depfile(content: &str) -> Vec<String>{
+    let Some((_, deps)) = content.split_once(": ") else{
+        return vec![];
+    };
+    let deps = deps.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut files = vec![];
+    let mut current = String::new();
+    let mut chars = deps.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&' ') => {
+                current.push(' ');
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty(){
+                    files.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty(){
+        files.push(current);
+    }
+    files.into_iter().skip(1).collect()
+}
+///hash of every header in a depfile, unreadable headers are left out
+pub fn hash_dependencies(depfile: &str) -> BTreeMap<String, String>{
+    parse_depfile(depfile).into_iter()
+    .filter_map(|path| std::fs::read(&path).ok().map(|content| (path, hash_content(&content))))
+    .collect()
+}
@@ -8,7 +8,8 @@ mod config;
 mod bindgen;
 mod binder_rs;
 mod ast;
-pub use config::ValueType;
+mod manifest;
+pub use config::{ValueType, CustomSettings};
 ///string not supported yet
 fn get_engine_str(type_str: &str) -> Option<(String, ValueType)>{
     match type_str {
@@ -20,9 +21,41 @@ fn get_engine_str(type_str: &str) -> Option<(String, ValueType)>{
     }
 }
 fn get_object_type(type_str: &str) -> (String, ValueType){    
-    (format!("{}", type_str), ValueType::Object)
+    (type_str.to_string(), ValueType::Object)
+}
+///unreal smart pointers of UObject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartPtr{
+    ObjectPtr,
+    SoftObjectPtr,
+    WeakObjectPtr,
+    ///UClass of the pointee or its subclass
+    SubclassOf,
+}
+impl SmartPtr{
+    ///`TObjectPtr<class AActor>` -> (ObjectPtr, `AActor`)
+    pub fn parse(type_str: &str) -> Option<(Self, String)>{
+        let (kind, pointee) = [
+            ("TObjectPtr<", Self::ObjectPtr), 
+            ("TSoftObjectPtr<", Self::SoftObjectPtr), 
+            ("TWeakObjectPtr<", Self::WeakObjectPtr), 
+            ("TSubclassOf<", Self::SubclassOf)
+        ]
+        .into_iter()
+        .find_map(|(prefix, kind)| type_str.trim().strip_prefix(prefix).map(|pointee| (kind, pointee)))?;
+        let pointee = pointee.strip_suffix('>')?.replace("const ", "").replace("class ", "").replace("struct ", "");
+        let pointee = pointee.trim();
+        if pointee.is_empty() || pointee.contains(['<', '*', ',']){
+            return None;
+        }
+        Some((kind, pointee.to_string()))
+    }
 }
 fn parse_c_type(type_str: &str) -> (String, ValueType){
+    //smart pointers are opaque pointer of pointee
+    if let Some((_, pointee)) = SmartPtr::parse(type_str){
+        return get_object_type(&pointee);
+    }
     crate::get_c2r_types(type_str).unwrap_or_else(||{
         get_engine_str(type_str)
         .unwrap_or(
@@ -54,7 +87,7 @@ fn parse_parameter(parameters: &Vec<Vec<String>>) -> Vec<Parameter>{
         // else{
         //     format!("arg{}", arg_index)
         // };
-        while let Some(next) =  iter.next(){            
+        for next in iter{            
             match next.as_str() {
                 "const" => {
                     parameter.const_param = true;
@@ -122,7 +155,7 @@ fn parse_function(api: &mut CppApi, fn_impl: &Vec<String>){
     //
     //println!("fn_impl {:?}", fn_impl);
     let mut iter = fn_impl.iter().rev();
-    api.name = iter.next().cloned().expect(&format!("parse function fail {:?}", fn_impl));
+    api.name = iter.next().cloned().unwrap_or_else(|| panic!("parse function fail {:?}", fn_impl));
     if api.class_name == api.name{
         api.is_construstor = true;
         api.rc_type = api.class_name.clone();
@@ -132,7 +165,7 @@ fn parse_function(api: &mut CppApi, fn_impl: &Vec<String>){
         api.is_destructor = true;
         return;
     }
-    while let Some(flag) = iter.next() {
+    for flag in iter {
         match flag.to_lowercase().as_str(){
             "static" => api.is_static = true,
             "const" => api.is_const = true,
@@ -163,4 +196,8 @@ fn parse_function(api: &mut CppApi, fn_impl: &Vec<String>){
 #[inline]
 pub fn is_false(val: &bool) -> bool{
     !*val
+}
+#[inline]
+pub fn is_zero(val: &usize) -> bool{
+    *val == 0
 }
\ No newline at end of file
@@ -5,26 +5,124 @@ use super::config::*;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UnrealClass{    
     pub name: String,
+    ///primary public base class
     pub inherit: String,
+    ///all base classes from `inherit` to the root
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub inherit_chain: Vec<String>,
     pub path: String,
     #[serde(skip_serializing_if = "super::is_false", default)]
     pub is_struct: bool,
+    ///union, members of anonymous unions are generated as `{Owner}_Union{N}`
+    #[serde(skip_serializing_if = "super::is_false", default)]
+    pub is_union: bool,
+    ///union or struct without type name(`{Owner}_Union{N}`/`{Owner}_Struct{N}`), it is only defined in rust
+    #[serde(skip_serializing_if = "super::is_false", default)]
+    pub is_unnamed: bool,
     #[serde(skip_serializing_if = "super::is_false", default)]
     pub opaque: bool,
     pub properties: Vec<CppProperty>,
     pub none_public_properties: Vec<CppProperty>,
     pub public_apis: Vec<CppApi>,
+    ///protected virtual functions, which can be overridden by generated subclass
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub virtual_apis: Vec<CppApi>,
+    ///UCLASS/USTRUCT specifiers
+    #[serde(skip_serializing_if = "Specifiers::is_empty", default)]
+    pub specifiers: Specifiers,
+    ///size and align reported by clang record layout, 0 if not dumped
+    #[serde(skip_serializing_if = "super::is_zero", default)]
+    pub size: usize,
+    #[serde(skip_serializing_if = "super::is_zero", default)]
+    pub align: usize,
 }
+///bump when the saved engine model is not compatible with the parser any more
+pub const ENGINE_SCHEMA_VERSION: u32 = 19;
 #[derive(Default, Serialize, Deserialize)]
 pub struct Engine{
-    #[serde(skip_serializing, default)]
+    ///schema version of the saved engine model
+    #[serde(default)]
+    pub schema_version: u32,
+    ///hash of engine root and parse settings this model was parsed with
+    #[serde(default)]
+    pub input_hash: String,
+    #[serde(default)]
     pub files: Vec<String>,
-    #[serde(skip_serializing, default)]
+    #[serde(default)]
     pub file_paths: BTreeMap<String, String>,
+    ///hash of scanned engine headers, key is the path relative to Source/Runtime
+    #[serde(skip)]
+    pub source_hashes: BTreeMap<String, String>,
     pub static_apis: Vec<CppApi>,
     pub classes: Vec<UnrealClass>,
     pub enums: Vec<CppEnum>,
     pub value_types: Vec<CppEnum>,
+    ///dynamic delegate signatures
+    #[serde(default)]
+    pub delegates: Vec<CppDelegate>,
+    ///sizeof(void*) of the clang target, 0 if unknown
+    #[serde(default)]
+    pub pointer_size: usize,
+    ///size and align of TypeWrapper rust types(wrapper.rs or field mapping), key is the rust type, computed for generation
+    #[serde(skip)]
+    pub wrapper_layouts: BTreeMap<String, (usize, usize)>,
 }
 unsafe impl Send for Engine{}
-unsafe impl Sync for Engine{}
\ No newline at end of file
+unsafe impl Sync for Engine{}
+impl Engine{
+    ///mark the model as parsed from current inputs
+    pub fn stamp(&mut self, settings: &CustomSettings){
+        self.schema_version = ENGINE_SCHEMA_VERSION;
+        self.input_hash = settings.input_hash();
+    }
+    ///resolve inherit chain of all classes, bases may come from other headers
+    pub fn resolve_inheritance(&mut self){
+        let parents = self.classes.iter()
+        .filter(|class| !class.inherit.is_empty())
+        .map(|class| (class.name.clone(), class.inherit.clone()))
+        .collect::<BTreeMap<_, _>>();
+        for class in &mut self.classes {
+            class.inherit_chain.clear();
+            let mut parent = &class.inherit;
+            while !parent.is_empty() && !class.inherit_chain.contains(parent) && parent != &class.name {
+                class.inherit_chain.push(parent.clone());
+                match parents.get(parent) {
+                    Some(next) => parent = next,
+                    None => break,
+                }
+            }
+        }
+    }
+    ///saved model is parsed by this schema from the same inputs
+    pub fn is_up_to_date(&self, settings: &CustomSettings) -> bool{
+        self.schema_version == ENGINE_SCHEMA_VERSION && self.input_hash == settings.input_hash()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn saved_model_is_reused_until_inputs_change() {
+        let mut settings = CustomSettings{ EngineRoot: "/engine".into(), ..Default::default() };
+        let mut engine = Engine::default();
+        assert!(!engine.is_up_to_date(&settings));
+        engine.stamp(&settings);
+        assert!(engine.is_up_to_date(&settings));
+        //export settings only affect generation
+        settings.ExportEnums.push("ETeleportType".into());
+        assert!(engine.is_up_to_date(&settings));
+        settings.Defines.push("WITH_EDITOR=0".into());
+        assert!(!engine.is_up_to_date(&settings));
+        engine.stamp(&settings);
+        settings.EngineRoot = "/other_engine".into();
+        assert!(!engine.is_up_to_date(&settings));
+    }
+    #[test]
+    fn saved_model_of_other_schema_is_outdated() {
+        let settings = CustomSettings::default();
+        let mut engine = Engine::default();
+        engine.stamp(&settings);
+        engine.schema_version -= 1;
+        assert!(!engine.is_up_to_date(&settings));
+    }
+}
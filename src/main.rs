@@ -1,8 +1,4 @@
-#[allow(unused)]
-#[macro_use] 
 extern crate serde_json;
-#[allow(unused)]
-#[macro_use] 
 extern crate anyhow;
 // include!("../Binders/rs/binders.rs");
 // mod api;
@@ -13,8 +9,10 @@ extern crate anyhow;
 // mod enum_parser;
 // mod binders;
 mod engine_parser;
-use std::collections::BTreeMap;
+mod cli;
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
 
+use clap::Parser;
 use engine_parser::ValueType;
 // use object::UnrealObject;
 use once_cell::sync::Lazy;
@@ -37,19 +35,16 @@ fn load_primaries() -> Vec<(String, String)>{
     ]
 }
 pub static RUST_TO_C_TYPES: Lazy<BTreeMap<String, (String, engine_parser::ValueType)>> = Lazy::new(||{
-    let map = 
     load_primaries()
     .into_iter()
     .enumerate()
     .map(|(index, (a, b))|{
         (a, (b, ValueType::from(index as i32)))
     })
-    .collect();
-    map
+    .collect()
 });
 
 static CPP_TO_RUST_TYPES: Lazy<BTreeMap<String, (String, engine_parser::ValueType)>> = Lazy::new(||{
-    let map = 
     load_primaries()
     .into_iter()
     .map(|(a,b)| (b, a))    
@@ -57,8 +52,7 @@ static CPP_TO_RUST_TYPES: Lazy<BTreeMap<String, (String, engine_parser::ValueTyp
     .map(|(index, (a, b))|{
         (a, (b, ValueType::from(index as i32)))
     })
-    .collect();
-    map
+    .collect()
 });
 pub fn get_c2r_types(key: &str) -> Option<(String, ValueType)>{
     CPP_TO_RUST_TYPES.get(key).cloned()
@@ -71,16 +65,16 @@ pub fn is_primary(type_str: &str) -> bool{
     CPP_TO_RUST_TYPES.get(type_str)
     .or(CPP_TO_RUST_TYPES.get(&format!("{type_str}_t"))).is_some()
 }
-fn read_files(path: &str, pattern: &str) -> anyhow::Result<Vec<String>> {
+fn read_files(path: impl AsRef<Path>, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
     let files = std::fs::read_dir(path)?;    //读出目录
     let mut output = Vec::new();
     for path in files {
         let entry = path?;
         if entry.file_type()?.is_dir(){
-            output.append(&mut read_files(entry.path().as_path().to_str().unwrap(), pattern)?);
+            output.append(&mut read_files(entry.path(), pattern)?);
         }
         else{
-            if pattern.len() > 0{
+            if !pattern.is_empty(){
                 match entry.path().extension(){
                     Some(ext) => {
                         if !pattern.contains(ext.to_str().unwrap()){
@@ -90,13 +84,17 @@ fn read_files(path: &str, pattern: &str) -> anyhow::Result<Vec<String>> {
                     None => continue,
                 }
             }
-            let path = entry.path().as_path().to_str().unwrap().replace("\\", "/").replace("\\\\", "/");
-            output.push(path);
+            output.push(entry.path());
         }
     }
-    
+    //read_dir order is platform dependent
+    output.sort();
     Ok(output)
 }
+///path to string with `/` separators, so the same path is identical on every platform
+pub fn path_to_string(path: impl AsRef<Path>) -> String{
+    path.as_ref().display().to_string().replace('\\', "/")
+}
 // fn load_binders(path: &str) -> anyhow::Result<Vec<UnrealObject>>{
 //     let files = read_files(path, "json")?;
 //     let mut output = Vec::new();
@@ -109,12 +107,16 @@ fn read_files(path: &str, pattern: &str) -> anyhow::Result<Vec<String>> {
 fn main() -> anyhow::Result<()> {
     // crate::ast::run()?;
     // return Ok(());
+    let cli = cli::Cli::parse();
+    let settings = cli.load_settings()?;
     let ins = std::time::Instant::now();
-    engine_parser::class_parser::parse(
-        serde_json::from_reader(
-            std::fs::File::open("configs/CustomSettings.json")?
-        )?
-    ).map_err(|e| {println!("{:?}", e); e})?;
+    match cli.command.unwrap_or(cli::Command::All) {
+        cli::Command::Scan => engine_parser::class_parser::scan(&settings).map(|_| ()),
+        cli::Command::Parse => engine_parser::class_parser::parse_engine(&settings).map(|_| ()),
+        cli::Command::Generate => engine_parser::class_parser::generate(&settings),
+        cli::Command::All => engine_parser::class_parser::parse(&settings).map(|_| ()),
+        cli::Command::CleanCache => engine_parser::class_parser::clean_cache(&settings),
+    }.map_err(|e| {println!("{:?}", e); e})?;
     println!("run finish cost {}", ins.elapsed().as_secs_f64());
     // let objects = load_binders("binders")?;
 